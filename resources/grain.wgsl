@@ -0,0 +1,32 @@
+struct Effect {
+    strength: f32,
+    time: f32,
+    width: f32,
+    height: f32,
+}
+
+@group(1) @binding(0)
+var t: texture_2d<f32>;
+@group(1) @binding(1)
+var s: sampler;
+@group(3) @binding(0)
+var<uniform> effect: Effect;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+fn hash(p: vec2<f32>) -> f32 {
+    return fract(sin(dot(p, vec2<f32>(12.9898, 78.233))) * 43758.5453);
+}
+
+// adds per-pixel noise, like old paper or film
+@fragment
+fn main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t, s, in.uv) * in.color;
+    let pixel = floor(in.uv * vec2<f32>(effect.width, effect.height));
+    let noise = hash(pixel + fract(effect.time)) - 0.5;
+    return vec4<f32>(color.rgb + noise * effect.strength * 0.25, color.a);
+}
@@ -0,0 +1,31 @@
+struct Effect {
+    strength: f32,
+    time: f32,
+    width: f32,
+    height: f32,
+}
+
+@group(1) @binding(0)
+var t: texture_2d<f32>;
+@group(1) @binding(1)
+var s: sampler;
+@group(3) @binding(0)
+var<uniform> effect: Effect;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+// blends towards a warm brown monochrome
+@fragment
+fn main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t, s, in.uv) * in.color;
+    let sepia = vec3<f32>(
+        dot(color.rgb, vec3<f32>(0.393, 0.769, 0.189)),
+        dot(color.rgb, vec3<f32>(0.349, 0.686, 0.168)),
+        dot(color.rgb, vec3<f32>(0.272, 0.534, 0.131)),
+    );
+    return vec4<f32>(mix(color.rgb, min(sepia, vec3<f32>(1.0)), effect.strength), color.a);
+}
@@ -0,0 +1,28 @@
+struct Effect {
+    strength: f32,
+    time: f32,
+    width: f32,
+    height: f32,
+}
+
+@group(1) @binding(0)
+var t: texture_2d<f32>;
+@group(1) @binding(1)
+var s: sampler;
+@group(3) @binding(0)
+var<uniform> effect: Effect;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+}
+
+// darkens the corners of the screen
+@fragment
+fn main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(t, s, in.uv) * in.color;
+    let dist = distance(in.uv, vec2<f32>(0.5, 0.5)) * 1.4142;
+    let shade = 1.0 - smoothstep(0.4, 1.0, dist) * effect.strength;
+    return vec4<f32>(color.rgb * shade, color.a);
+}
@@ -0,0 +1,547 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use ggez::{
+    conf::{NumSamples, WindowMode, WindowSetup},
+    GameError, GameResult,
+};
+
+use crate::models::{
+    cartouche::Style,
+    export::{self, ExportOptions},
+    logger::{Log, Timestamps},
+    parchment::Fit,
+    pdf::{self, PageSize},
+    post::Effect,
+};
+
+/// Startup settings, read from a config file and overridden by command line flags.
+///
+/// The file holds one `key = value` pair per line, `#` starts a comment.
+/// Every key can also be passed as `--key value`, booleans also accept `--key` and `--no-key`.
+#[derive(Debug, Clone)]
+pub struct Config {
+    width: f32,
+    height: f32,
+    resizable: bool,
+    vsync: bool,
+    samples: NumSamples,
+    export_width: u32,
+    export_height: u32,
+    export: ExportOptions,
+    log_timestamps: Timestamps,
+    /// Whether log entries start with their level glyph.
+    log_prefix: bool,
+    log_capacity: usize,
+    log_lines: usize,
+    log_file: Option<PathBuf>,
+    large_text: bool,
+    reduced_motion: bool,
+    sync_dir: Option<PathBuf>,
+    save_file: PathBuf,
+    roll20_grid: bool,
+    print_page: PageSize,
+    /// Page margin in inches.
+    print_margin: f32,
+    /// Printed size of a grid square in inches.
+    print_square: f32,
+    print_dpi: u32,
+    parchment_fit: Fit,
+    cartouche_style: Style,
+    /// Strength of each post effect while enabled, between 0 and 1: [sepia, grain, vignette]
+    effect_strength: [f32; 3],
+    /// File that settings changed at runtime are written back to.
+    path: PathBuf,
+}
+
+impl Config {
+    /// Config file read when `--config` is not given.
+    pub const DEFAULT_PATH: &'static str = "cartographer.conf";
+
+    /// Builds the config from the command line arguments, excluding the program name.
+    ///
+    /// The file named by `--config`, or [`Self::DEFAULT_PATH`] if it exists, is read first.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> GameResult<Config> {
+        let args: Vec<String> = args.into_iter().collect();
+        let mut config = Config::default();
+
+        match args.iter().position(|arg| arg == "--config") {
+            Some(i) => {
+                let path = args.get(i + 1).ok_or_else(|| missing("config"))?;
+                config.load(path)?;
+                config.path = PathBuf::from(path);
+            }
+            None if Path::new(Self::DEFAULT_PATH).exists() => config.load(Self::DEFAULT_PATH)?,
+            None => (),
+        }
+
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            let Some(key) = arg.strip_prefix("--") else {
+                return Err(GameError::ConfigError(format!(
+                    "unexpected argument: {arg}"
+                )));
+            };
+            if let Some(key) = key.strip_prefix("no-") {
+                config.set(key, "false")?;
+            } else if config.is_flag(key) {
+                // the value of a boolean is optional: `--vsync` or `--vsync false`
+                let value = args
+                    .next_if(|value| value == "true" || value == "false")
+                    .unwrap_or_else(|| "true".to_string());
+                config.set(key, &value)?;
+            } else {
+                let value = args.next().ok_or_else(|| missing(key))?;
+                if key != "config" {
+                    config.set(key, &value)?;
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    /// Reads `key = value` pairs from the file at `path`.
+    pub fn load(&mut self, path: impl AsRef<Path>) -> GameResult {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|e| {
+            GameError::ConfigError(format!("could not read {}: {e}", path.display()))
+        })?;
+        for line in contents.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                GameError::ConfigError(format!("expected `key = value`, found: {line}"))
+            })?;
+            self.set(key.trim(), value.trim())?;
+        }
+        Ok(())
+    }
+
+    /// Writes `key = value` to the config file, replacing an earlier line for `key`.
+    ///
+    /// Other lines and comments are kept, the file is created if needed.
+    fn persist(&self, key: &str, value: &str) -> GameResult {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(GameError::ConfigError(format!(
+                    "could not read {}: {e}",
+                    self.path.display()
+                )))
+            }
+        };
+        let setting = format!("{key} = {value}");
+        let mut replaced = false;
+        let mut lines: Vec<String> = contents
+            .lines()
+            .map(|line| {
+                let current = line.split('#').next().unwrap_or_default();
+                match current.split_once('=') {
+                    Some((k, _)) if !replaced && k.trim() == key => {
+                        replaced = true;
+                        setting.clone()
+                    }
+                    _ => line.to_string(),
+                }
+            })
+            .collect();
+        if !replaced {
+            lines.push(setting);
+        }
+        fs::write(&self.path, lines.join("\n") + "\n").map_err(|e| {
+            GameError::ConfigError(format!("could not write {}: {e}", self.path.display()))
+        })
+    }
+
+    /// Returns the window mode for the [`ggez::ContextBuilder`].
+    pub fn window_mode(&self) -> WindowMode {
+        WindowMode::default()
+            .dimensions(self.width, self.height)
+            .resizable(self.resizable)
+    }
+
+    /// Returns the size of high resolution map exports: (width, height).
+    pub fn export_size(&self) -> (u32, u32) {
+        (self.export_width, self.export_height)
+    }
+
+    /// Returns the settings of raster map exports.
+    pub fn export_options(&self) -> ExportOptions {
+        self.export
+    }
+
+    /// Returns the directory exports are mirrored to, e.g. a Dropbox or Syncthing folder.
+    pub fn sync_dir(&self) -> Option<&Path> {
+        self.sync_dir.as_deref()
+    }
+
+    /// Returns true if Roll20 exports have the grid baked into the image.
+    pub fn roll20_grid(&self) -> bool {
+        self.roll20_grid
+    }
+
+    /// Returns the paper size of PDF exports.
+    pub fn print_page(&self) -> PageSize {
+        self.print_page
+    }
+
+    /// Returns the page margin of PDF exports in inches.
+    pub fn print_margin(&self) -> f32 {
+        self.print_margin
+    }
+
+    /// Returns the printed size of a grid square in inches.
+    pub fn print_square(&self) -> f32 {
+        self.print_square
+    }
+
+    /// Returns the resolution of the map image in PDF exports.
+    pub fn print_dpi(&self) -> u32 {
+        self.print_dpi
+    }
+
+    /// Returns how the parchment texture covers the screen.
+    pub fn parchment_fit(&self) -> Fit {
+        self.parchment_fit
+    }
+
+    /// Returns the wording of generated cartouche text.
+    pub fn cartouche_style(&self) -> Style {
+        self.cartouche_style
+    }
+
+    /// Returns the strength of `effect` while it is enabled, between 0 and 1.
+    pub fn effect_strength(&self, effect: Effect) -> f32 {
+        match effect {
+            Effect::Sepia => self.effect_strength[0],
+            Effect::Grain => self.effect_strength[1],
+            Effect::Vignette => self.effect_strength[2],
+        }
+    }
+
+    /// Returns the file the map is saved to and loaded from.
+    pub fn save_file(&self) -> &Path {
+        &self.save_file
+    }
+
+    /// Returns how log entry times are shown.
+    pub fn log_timestamps(&self) -> Timestamps {
+        self.log_timestamps
+    }
+
+    /// Returns whether log entries start with their level glyph.
+    pub fn log_prefix(&self) -> bool {
+        self.log_prefix
+    }
+
+    /// Returns the number of log entries kept before the oldest are dropped.
+    pub fn log_capacity(&self) -> usize {
+        self.log_capacity
+    }
+
+    /// Returns the number of lines shown in the log box.
+    pub fn log_lines(&self) -> usize {
+        self.log_lines
+    }
+
+    /// Returns the file the log is mirrored to, if any.
+    pub fn log_file(&self) -> Option<&Path> {
+        self.log_file.as_deref()
+    }
+
+    /// Returns true if UI text is drawn larger, independent of the map zoom.
+    pub fn large_text(&self) -> bool {
+        self.large_text
+    }
+
+    /// Enables or disables large UI text and saves the choice to the config file.
+    pub fn set_large_text(&mut self, large_text: bool) -> GameResult {
+        self.large_text = large_text;
+        self.persist("large-text", &large_text.to_string())
+    }
+
+    /// Returns true if animations such as particles and palette fades are disabled.
+    pub fn reduced_motion(&self) -> bool {
+        self.reduced_motion
+    }
+
+    /// Enables or disables reduced motion and saves the choice to the config file.
+    pub fn set_reduced_motion(&mut self, reduced_motion: bool) -> GameResult {
+        self.reduced_motion = reduced_motion;
+        self.persist("reduced-motion", &reduced_motion.to_string())
+    }
+
+    /// Returns the number of samples per pixel the map is rendered with.
+    pub fn samples(&self) -> u32 {
+        match self.samples {
+            NumSamples::One => 1,
+            NumSamples::Four => 4,
+        }
+    }
+
+    /// Returns the window setup for the [`ggez::ContextBuilder`].
+    pub fn window_setup(&self, title: &str) -> WindowSetup {
+        WindowSetup::default()
+            .title(title)
+            .vsync(self.vsync)
+            .samples(self.samples)
+    }
+
+    fn is_flag(&self, key: &str) -> bool {
+        matches!(
+            key,
+            "resizable"
+                | "vsync"
+                | "large-text"
+                | "reduced-motion"
+                | "roll20-grid"
+                | "log-prefix"
+                | "export-transparent"
+                | "export-ui"
+        )
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> GameResult {
+        match key {
+            "width" => self.width = positive(key, parse(key, value)?)?,
+            "height" => self.height = positive(key, parse(key, value)?)?,
+            "resizable" => self.resizable = parse(key, value)?,
+            "vsync" => self.vsync = parse(key, value)?,
+            "export-width" => self.export_width = export_side(key, parse(key, value)?)?,
+            "export-height" => self.export_height = export_side(key, parse(key, value)?)?,
+            "export-scale" => self.export.scale = positive(key, parse(key, value)?)?,
+            "export-margin" => self.export.margin = parse(key, value)?,
+            "export-transparent" => self.export.transparent = parse(key, value)?,
+            "export-ui" => self.export.include_ui = parse(key, value)?,
+            "log-timestamps" => self.log_timestamps = parse(key, value)?,
+            "log-prefix" => self.log_prefix = parse(key, value)?,
+            "log-capacity" => self.log_capacity = parse(key, value)?,
+            "log-lines" => self.log_lines = parse(key, value)?,
+            "print-page" => self.print_page = parse(key, value)?,
+            "print-margin" => self.print_margin = parse::<f32>(key, value)?.max(0.0),
+            "print-square" => self.print_square = positive(key, parse(key, value)?)?,
+            "print-dpi" => self.print_dpi = dpi(key, parse(key, value)?)?,
+            "roll20-grid" => self.roll20_grid = parse(key, value)?,
+            "parchment-fit" => self.parchment_fit = parse(key, value)?,
+            "cartouche-style" => self.cartouche_style = parse(key, value)?,
+            "sepia-strength" => self.effect_strength[0] = fraction(key, value)?,
+            "grain-strength" => self.effect_strength[1] = fraction(key, value)?,
+            "vignette-strength" => self.effect_strength[2] = fraction(key, value)?,
+            "save-file" => self.save_file = PathBuf::from(value),
+            "sync-dir" => self.sync_dir = Some(PathBuf::from(value)),
+            "large-text" => self.large_text = parse(key, value)?,
+            "reduced-motion" => self.reduced_motion = parse(key, value)?,
+            "log-file" => self.log_file = Some(PathBuf::from(value)),
+            "msaa" => {
+                self.samples = match parse::<u8>(key, value)? {
+                    1 => NumSamples::One,
+                    4 => NumSamples::Four,
+                    n => {
+                        return Err(GameError::ConfigError(format!(
+                            "msaa: unsupported sample count {n}, expected 1 or 4"
+                        )))
+                    }
+                }
+            }
+            _ => return Err(GameError::ConfigError(format!("unknown setting: {key}"))),
+        }
+        Ok(())
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            width: 1280.0,
+            height: 720.0,
+            resizable: false,
+            vsync: true,
+            samples: NumSamples::One,
+            export_width: 4096,
+            export_height: 4096,
+            export: ExportOptions::default(),
+            log_timestamps: Timestamps::Off,
+            log_prefix: true,
+            log_capacity: Log::DEFAULT_CAPACITY,
+            log_lines: Log::DEFAULT_LINES,
+            log_file: None,
+            large_text: false,
+            reduced_motion: false,
+            sync_dir: None,
+            save_file: PathBuf::from("map.json"),
+            roll20_grid: false,
+            print_page: PageSize::Letter,
+            print_margin: 0.5,
+            print_square: 1.0,
+            print_dpi: 150,
+            parchment_fit: Fit::Tile,
+            cartouche_style: Style::Latin,
+            effect_strength: [1.0; 3],
+            path: PathBuf::from(Self::DEFAULT_PATH),
+        }
+    }
+}
+
+fn parse<T: std::str::FromStr>(key: &str, value: &str) -> GameResult<T> {
+    value
+        .parse()
+        .map_err(|_| GameError::ConfigError(format!("{key}: invalid value `{value}`")))
+}
+
+fn fraction(key: &str, value: &str) -> GameResult<f32> {
+    Ok(parse::<f32>(key, value)?.clamp(0.0, 1.0))
+}
+
+fn positive(key: &str, value: f32) -> GameResult<f32> {
+    match value > 0.0 {
+        true => Ok(value),
+        false => Err(GameError::ConfigError(format!(
+            "{key}: expected a positive number, got {value}"
+        ))),
+    }
+}
+
+/// Rejects an empty export side and clamps it to the GPU texture limit.
+fn export_side(key: &str, value: u32) -> GameResult<u32> {
+    match value {
+        0 => Err(GameError::ConfigError(format!(
+            "{key}: expected at least 1 pixel"
+        ))),
+        _ => Ok(value.min(export::MAX_SIZE)),
+    }
+}
+
+/// Rejects print resolutions outside 1 to [`pdf::MAX_DPI`] dots per inch.
+fn dpi(key: &str, value: u32) -> GameResult<u32> {
+    match value {
+        1..=pdf::MAX_DPI => Ok(value),
+        _ => Err(GameError::ConfigError(format!(
+            "{key}: expected 1 to {} dots per inch, got {value}",
+            pdf::MAX_DPI
+        ))),
+    }
+}
+
+fn missing(key: &str) -> GameError {
+    GameError::ConfigError(format!("--{key} expects a value"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    /// Returns a path in the temp directory unique to the calling test.
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!(
+            "cartographer-config-{name}-{}.conf",
+            std::process::id()
+        ))
+    }
+
+    #[test]
+    fn flags_and_values() {
+        let config = Config::from_args(args(&[
+            "--resizable",
+            "--no-vsync",
+            "--no-log-prefix",
+            "--msaa",
+            "4",
+            "--width",
+            "1920",
+        ]))
+        .unwrap();
+        assert!(config.resizable);
+        assert!(!config.vsync);
+        assert!(!config.log_prefix());
+        assert_eq!(config.samples(), 4);
+        assert_eq!(config.width, 1920.0);
+    }
+
+    #[test]
+    fn flags_take_an_explicit_value() {
+        let config =
+            Config::from_args(args(&["--vsync", "false", "--large-text", "true"])).unwrap();
+        assert!(!config.vsync);
+        assert!(config.large_text);
+    }
+
+    #[test]
+    fn rejects_bad_arguments() {
+        assert!(Config::from_args(args(&["--width", "0"])).is_err());
+        assert!(Config::from_args(args(&["--export-width", "0"])).is_err());
+        assert!(Config::from_args(args(&["--print-dpi", "0"])).is_err());
+        assert!(Config::from_args(args(&["--print-dpi", "100000"])).is_err());
+        assert!(Config::from_args(args(&["--msaa", "2"])).is_err());
+        assert!(Config::from_args(args(&["--colour", "red"])).is_err());
+        assert!(Config::from_args(args(&["--height"])).is_err());
+        assert!(Config::from_args(args(&["vsync"])).is_err());
+    }
+
+    #[test]
+    fn config_file_with_overrides() {
+        let path = temp_path("file");
+        fs::write(
+            &path,
+            "# window\nwidth = 800 # narrow\n\nvsync = false\nheight = 400\n",
+        )
+        .unwrap();
+        let config = Config::from_args(args(&[
+            "--config",
+            path.to_str().unwrap(),
+            "--height",
+            "600",
+        ]));
+        fs::remove_file(&path).unwrap();
+
+        let config = config.unwrap();
+        assert_eq!(config.width, 800.0);
+        assert_eq!(config.height, 600.0);
+        assert!(!config.vsync);
+        assert_eq!(config.path, path);
+    }
+
+    #[test]
+    fn persist_replaces_and_appends() {
+        let path = temp_path("persist");
+        fs::write(&path, "width = 800\n# keep\nlarge-text = false # old\n").unwrap();
+        let config = Config {
+            path: path.clone(),
+            ..Config::default()
+        };
+        config.persist("large-text", "true").unwrap();
+        config.persist("reduced-motion", "true").unwrap();
+        let contents = fs::read_to_string(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            contents.unwrap(),
+            "width = 800\n# keep\nlarge-text = true\nreduced-motion = true\n"
+        );
+    }
+
+    #[test]
+    fn persist_creates_the_file() {
+        let path = temp_path("create");
+        let _ = fs::remove_file(&path);
+        let config = Config {
+            path: path.clone(),
+            ..Config::default()
+        };
+        config.persist("vsync", "false").unwrap();
+        let contents = fs::read_to_string(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(contents.unwrap(), "vsync = false\n");
+    }
+}
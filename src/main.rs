@@ -1,21 +1,79 @@
+mod config;
 mod models;
+mod util;
 
-use std::{env, ops::DerefMut, path};
+use std::{env, fs, ops::DerefMut, path};
 
 use cartography_core::{colors, seed};
+use config::Config;
 use ggez::{
-    conf::{WindowMode, WindowSetup},
     event::{self, EventHandler},
-    graphics, Context, GameResult,
+    glam::Vec2,
+    graphics::{self, Image, ImageFormat},
+    input::mouse::MouseButton,
+    Context, GameResult,
 };
-use models::logger::{Log, TextParams};
+use models::{
+    camera::Camera,
+    cartouche::{Cartouche, Line},
+    export::{self, ExportOptions},
+    grid::{Grid, GridShape},
+    layers::{canvas_bounds, Layer, LayerKind},
+    legend::{Legend, Symbol},
+    lighting::Lighting,
+    logger::{Category, Handle, Level, Log, Span, TextParams, Tone},
+    parchment::Parchment,
+    particles::{Particle, ParticleSystem},
+    pdf,
+    post::{Effect, PostProcess},
+    save::{self, CameraState, CartoucheState, EffectState, GridState, LightingState, MapState},
+    swatches::{self, PaletteFormat},
+    transition::PaletteTransition,
+};
+use util::{clipboard::Clipboard, poisson::PoissonDisk, time::DateTime};
 
 #[allow(unused)]
 #[derive(Debug)]
 struct Cartographer {
+    config: Config,
     palette: colors::Palette,
+    /// Colors currently on screen: (foreground, background), trailing `palette` during a transition.
+    colors: ([f32; 4], [f32; 4]),
+    transition: Option<PaletteTransition>,
     seed: seed::Seed,
     log: Log,
+    /// Part of the log box being dragged with the left button.
+    drag: Option<Handle>,
+    /// Set while the log search query is being typed.
+    typing: bool,
+    camera: Camera,
+    particles: ParticleSystem,
+    grid: Grid,
+    /// Index of the layer targeted by the lock and solo toggles, in draw order.
+    selected: usize,
+    /// Set once a layer was selected with Tab, the selected layer is outlined from then on.
+    show_focus: bool,
+    /// Names of layers that ignore edits and regeneration.
+    locked: Vec<String>,
+    /// Name of the only map layer drawn, if any.
+    solo: Option<String>,
+    parchment: Option<Parchment>,
+    lighting: Lighting,
+    cartouche: Cartouche,
+    legend: Legend,
+    post: PostProcess,
+    /// Map layers before post-processing.
+    scene: graphics::ScreenImage,
+    /// Multisampled target resolved into `scene`, if MSAA is enabled.
+    msaa: Option<graphics::ScreenImage>,
+    /// Last rendered frame, redrawn only when `dirty` is set.
+    frame: graphics::ScreenImage,
+    dirty: bool,
+    /// Set to save the next presented frame as a PNG.
+    screenshot: bool,
+    clipboard: Clipboard,
+    /// Outcome of the last copy to the sync directory, shown in the window title.
+    sync_status: Option<String>,
     counter: u32,
 }
 
@@ -27,8 +85,30 @@ impl Cartographer {
     const BORDER: f32 = 50.0;
     const TEXT_HEIGHT: f32 = 16.0;
     const FONT: &'static str = "JetBrains Mono";
+    const PARCHMENT: &'static str = "/parchment.png";
+    const PAN_STEP: f32 = 32.0;
+    /// Distance the focused log box moves or grows per arrow key press.
+    const LOG_STEP: f32 = 8.0;
+    const ZOOM_STEP: f32 = 1.1;
+    const MAX_PARTICLES: usize = 4096;
+    const GRID_CELL: f32 = 64.0;
+    const GRID_OPACITY: f32 = 0.35;
+    const NIGHT: [f32; 4] = [0.02, 0.03, 0.12, 1.0];
+    const DAY_LENGTH: f32 = 60.0;
+    const PALETTE_FADE: f32 = 0.5;
+    /// Space between the selected layer and its focus outline.
+    const FOCUS_GAP: f32 = 4.0;
+    /// Screen pixels covered by the sparks of a click on the map.
+    const PING_RADIUS: f32 = 48.0;
+    const PING_SPARK: f32 = 3.0;
+    /// Seconds until the sparks of a click fade out.
+    const PING_LIFETIME: f32 = 0.6;
+    /// Scale of UI text in large-text mode.
+    const LARGE_TEXT: f32 = 1.5;
+    /// Pixels per grid square expected by Roll20.
+    const ROLL20_SQUARE: u32 = 70;
 
-    pub fn new(ctx: &mut Context) -> GameResult<Cartographer> {
+    pub fn new(ctx: &mut Context, config: Config) -> GameResult<Cartographer> {
         let mut seed = seed::Seed::new();
 
         let palette = colors::Palette::random(seed.deref_mut(), 1.0, 1.0);
@@ -45,37 +125,980 @@ impl Cartographer {
             Self::THIN_LINE,
         );
 
-        let log = Log::new(txt_params, ctx)?;
+        let cartouche = Cartouche::generate(
+            seed.deref_mut(),
+            config.cartouche_style(),
+            txt_params.clone(),
+            Self::BORDER,
+        );
+        let legend = Legend::new(txt_params.clone(), Self::BORDER);
+
+        let mut log = Log::new(txt_params, (Self::BORDER, Self::BORDER), ctx)?;
+        log.set_timestamps(config.log_timestamps());
+        log.set_prefix(config.log_prefix());
+        log.set_capacity(config.log_capacity());
+        log.set_visible_lines(ctx, config.log_lines())?;
+        if let Err(e) = log.install(::log::LevelFilter::Info) {
+            log.warn(format!("{e}"));
+        }
+        if let Some(path) = config.log_file() {
+            if let Err(e) = log.mirror_to(path) {
+                log.error(format!("Could not open log file {}: {e}", path.display()));
+            }
+        }
+
+        // the texture is optional, fall back to the flat background color
+        let parchment =
+            match Parchment::load(ctx, Self::PARCHMENT, *palette.bg(), config.parchment_fit()) {
+                Ok(parchment) => Some(parchment),
+                Err(e) => {
+                    log.warn(format!("No parchment texture: {e}"));
+                    None
+                }
+            };
+
+        let grid = Grid::new(
+            GridShape::Square,
+            Self::GRID_CELL,
+            Self::GRID_OPACITY,
+            *palette.fg(),
+            Self::THIN_LINE,
+        );
+
+        // the map is drawn multisampled and resolved into `scene`
+        let msaa = match config.samples() {
+            1 => None,
+            samples => Some(graphics::ScreenImage::new(ctx, None, 1.0, 1.0, samples)),
+        };
 
-        Ok(Cartographer {
+        let mut cartographer = Cartographer {
+            config,
+            colors: (*palette.fg(), *palette.bg()),
+            transition: None,
             palette,
             seed,
             counter: 0,
             log,
-        })
+            drag: None,
+            typing: false,
+            camera: Camera::new(),
+            particles: ParticleSystem::new(ctx, Self::MAX_PARTICLES),
+            grid,
+            selected: 0,
+            show_focus: false,
+            locked: vec![],
+            solo: None,
+            parchment,
+            lighting: Lighting::new(21.0, 0.0, Self::NIGHT),
+            cartouche,
+            legend,
+            post: PostProcess::new(ctx)?,
+            scene: graphics::ScreenImage::new(ctx, None, 1.0, 1.0, 1),
+            msaa,
+            // RGBA so the frame can be written out as a screenshot
+            frame: graphics::ScreenImage::new(ctx, ImageFormat::Rgba8UnormSrgb, 1.0, 1.0, 1),
+            dirty: true,
+            screenshot: false,
+            clipboard: Clipboard::default(),
+            sync_status: None,
+        };
+        for effect in Effect::ALL {
+            let strength = cartographer.config.effect_strength(effect);
+            cartographer.post.set_strength(effect, strength);
+        }
+        cartographer.apply_text_size(ctx)?;
+        cartographer.apply_motion();
+        Ok(cartographer)
+    }
+
+    /// Sizes the UI text for the large-text setting, map text keeps its size.
+    fn apply_text_size(&mut self, ctx: &Context) -> GameResult {
+        let height = match self.config.large_text() {
+            true => Self::TEXT_HEIGHT * Self::LARGE_TEXT,
+            false => Self::TEXT_HEIGHT,
+        };
+        self.legend.set_text_height(height);
+        self.log.set_text_height(ctx, height)
+    }
+
+    /// Turns particles, the day/night clock and animated effects off or on
+    /// for the reduced-motion setting.
+    fn apply_motion(&mut self) {
+        let reduced = self.config.reduced_motion();
+        self.particles.set_visible(!reduced);
+        if reduced {
+            self.particles.clear();
+            self.lighting.set_speed(0.0);
+        }
+        self.post.set_animated(!reduced);
+    }
+
+    /// Warns if a setting changed at runtime could not be saved, it still applies to this session.
+    fn report_setting(&mut self, saved: GameResult) {
+        if let Err(e) = saved {
+            self.log.push_in(
+                Category::System,
+                Level::Warn,
+                format!("Setting not saved: {e}"),
+            );
+        }
+    }
+
+    /// Returns every layer of the scene, in no particular order.
+    ///
+    /// New layers need to be listed here to be drawn.
+    fn layers(&mut self) -> Vec<&mut dyn Layer> {
+        let mut layers = vec![
+            &mut self.particles as &mut dyn Layer,
+            &mut self.grid,
+            &mut self.lighting,
+            &mut self.cartouche,
+            &mut self.legend,
+            &mut self.log,
+        ];
+        if let Some(parchment) = &mut self.parchment {
+            layers.push(parchment);
+        }
+        layers
+    }
+
+    /// Returns the layer names in draw order.
+    fn layer_names(&mut self) -> Vec<String> {
+        let mut layers = self.layers();
+        layers.sort_by_key(|layer| layer.kind());
+        layers
+            .iter()
+            .map(|layer| layer.name().to_string())
+            .collect()
+    }
+
+    /// Returns the name of the layer targeted by the lock and solo toggles.
+    fn selected_layer(&mut self) -> String {
+        let names = self.layer_names();
+        names[self.selected % names.len()].clone()
+    }
+
+    /// Returns true if the layer called `name` may be edited, logging a warning otherwise.
+    fn editable(&mut self, name: &str) -> bool {
+        let locked = self.locked.iter().any(|locked| locked == name);
+        if locked {
+            self.log
+                .push_in(Category::Input, Level::Warn, format!("{name} is locked"));
+        }
+        !locked
+    }
+
+    /// Sends a burst of sparks out from `point` (in screen coordinates) to mark a spot on the map.
+    ///
+    /// The sparks spread evenly over a disc, each slowing to a stop at a Poisson-disk sample point.
+    /// Nothing is emitted while particles are hidden, e.g. under reduced motion.
+    fn ping(&mut self, point: Vec2) {
+        if !self.particles.visible() {
+            return;
+        }
+        let zoom = self.camera.zoom();
+        let center = self.camera.screen_to_world(point);
+        let radius = Self::PING_RADIUS / zoom;
+        let color = graphics::Color::from(Tone::Accent.color(self.colors.0, Level::Info));
+        let sampler = PoissonDisk::new(
+            radius * 2.0,
+            radius * 2.0,
+            radius / 4.0,
+            PoissonDisk::DEFAULT_ATTEMPTS,
+        );
+        let offsets = sampler
+            .sample(&mut rand::thread_rng())
+            .into_iter()
+            .map(|sample| sample - Vec2::splat(radius))
+            .filter(|offset| offset.length() <= radius);
+        for offset in offsets {
+            // constant deceleration covers `offset` in exactly one lifetime
+            let velocity = offset * 2.0 / Self::PING_LIFETIME;
+            let spark = Particle::new(
+                center,
+                velocity,
+                color,
+                Self::PING_SPARK / zoom,
+                Self::PING_LIFETIME,
+            )
+            .with_acceleration(-velocity / Self::PING_LIFETIME);
+            if !self.particles.emit(spark) {
+                break;
+            }
+        }
+    }
+
+    /// Returns true if no layer recolored by [`Self::set_colors`] is locked, logging a warning otherwise.
+    fn recolorable(&mut self) -> bool {
+        ["grid", "cartouche", "legend", "parchment", "log"]
+            .into_iter()
+            .all(|name| self.editable(name))
+    }
+
+    fn toggle_lock(&mut self) {
+        let name = self.selected_layer();
+        let locked = match self.locked.iter().position(|locked| *locked == name) {
+            Some(i) => {
+                self.locked.remove(i);
+                false
+            }
+            None => {
+                self.locked.push(name.clone());
+                true
+            }
+        };
+        self.log.push_in(
+            Category::Input,
+            Level::Info,
+            format!("{name} {}", if locked { "locked" } else { "unlocked" }),
+        );
+    }
+
+    fn toggle_solo(&mut self) {
+        let name = self.selected_layer();
+        self.solo = match self.solo.as_deref() == Some(name.as_str()) {
+            true => None,
+            false => Some(name.clone()),
+        };
+        self.log.push_in(
+            Category::Input,
+            Level::Info,
+            match &self.solo {
+                Some(_) => format!("Showing only {name}"),
+                None => "Showing all layers".to_string(),
+            },
+        );
+    }
+
+    /// Applies the foreground and background colors to every part of the scene.
+    fn set_colors(&mut self, ctx: &mut Context, fg: [f32; 4], bg: [f32; 4]) -> GameResult {
+        self.colors = (fg, bg);
+        self.log.color_mut(ctx, fg)?;
+        self.grid.color_mut(fg);
+        self.cartouche.color_mut(fg);
+        self.legend.color_mut(fg);
+        if let Some(parchment) = &mut self.parchment {
+            parchment.color_mut(bg);
+        }
+        Ok(())
+    }
+
+    /// Writes the last rendered frame to a timestamped PNG next to the executable.
+    fn save_screenshot(&mut self, ctx: &mut Context) -> GameResult<path::PathBuf> {
+        let path = export::output_path("screenshot", "png")?;
+        export::save_png(ctx, &self.frame.image(ctx), &path)?;
+        Ok(path)
+    }
+
+    /// Renders the map into an image of the given size plus the margin, independent of the window.
+    ///
+    /// The current camera view is grown to the aspect ratio of the image.
+    /// Fails if the image with its margin exceeds [`export::MAX_SIZE`].
+    fn render_offscreen(
+        &mut self,
+        ctx: &mut Context,
+        width: u32,
+        height: u32,
+        options: &ExportOptions,
+    ) -> GameResult<Image> {
+        let border = options.margin.saturating_mul(2);
+        let (outer_width, outer_height) =
+            (width.saturating_add(border), height.saturating_add(border));
+        if outer_width > export::MAX_SIZE || outer_height > export::MAX_SIZE {
+            return Err(ggez::GameError::CustomError(format!(
+                "{outer_width} x {outer_height} pixels including the margin exceed the {} pixel limit",
+                export::MAX_SIZE
+            )));
+        }
+
+        let world = self.camera.view_fit(ctx, width as f32 / height as f32);
+        let background = match options.transparent {
+            true => graphics::Color::new(0.0, 0.0, 0.0, 0.0),
+            false => graphics::Color::from(self.colors.1),
+        };
+        let map = self.render_world(ctx, world, width, height, background, |kind| {
+            options.includes(kind)
+        })?;
+        if options.margin == 0 {
+            return Ok(map);
+        }
+
+        let margin = options.margin;
+        let image = Image::new_canvas_image(
+            ctx,
+            ImageFormat::Rgba8UnormSrgb,
+            outer_width,
+            outer_height,
+            1,
+        );
+        let mut canvas = graphics::Canvas::from_image(ctx, image.clone(), background);
+        canvas.draw(
+            &map,
+            graphics::DrawParam::default().dest(Vec2::splat(margin as f32)),
+        );
+        canvas.finish(ctx)?;
+        Ok(image)
+    }
+
+    /// Renders the `world` area of the layers accepted by `filter` into an image of the given size.
+    fn render_world(
+        &mut self,
+        ctx: &mut Context,
+        world: graphics::Rect,
+        width: u32,
+        height: u32,
+        background: graphics::Color,
+        filter: impl Fn(LayerKind) -> bool,
+    ) -> GameResult<Image> {
+        let image = Image::new_canvas_image(ctx, ImageFormat::Rgba8UnormSrgb, width, height, 1);
+        let mut canvas = graphics::Canvas::from_image(ctx, image.clone(), background);
+        let screen = graphics::Rect::new(0.0, 0.0, width as f32, height as f32);
+        self.draw_scene(ctx, &mut canvas, world, screen, filter)?;
+        canvas.finish(ctx)?;
+        Ok(image)
+    }
+
+    /// Returns the camera view grown to whole grid squares: (columns, rows, world area).
+    fn snapped_view(&self, ctx: &Context) -> (u32, u32, graphics::Rect) {
+        let cell = self.grid.cell_size();
+        let view = self.camera.view(ctx);
+        let left = (view.left() / cell).floor();
+        let top = (view.top() / cell).floor();
+        let columns = (view.right() / cell).ceil() - left;
+        let rows = (view.bottom() / cell).ceil() - top;
+        let world = graphics::Rect::new(left * cell, top * cell, columns * cell, rows * cell);
+        (columns as u32, rows as u32, world)
+    }
+
+    /// Returns the image size for `columns` x `rows` squares of `square` pixels,
+    /// or an error if it exceeds [`export::MAX_SIZE`].
+    fn squares_size(columns: u32, rows: u32, square: u32) -> GameResult<(u32, u32)> {
+        let fits = |count: u32| {
+            count
+                .checked_mul(square)
+                .filter(|side| *side <= export::MAX_SIZE)
+        };
+        match (fits(columns), fits(rows)) {
+            (Some(width), Some(height)) => Ok((width, height)),
+            _ => Err(ggez::GameError::CustomError(format!(
+                "{columns} x {rows} squares do not fit in a {max} pixel image, zoom in",
+                max = export::MAX_SIZE
+            ))),
+        }
+    }
+
+    /// Writes the view snapped to whole grid squares at Roll20's 70 pixels per square,
+    /// with a text note of the page size next to the PNG.
+    ///
+    /// The grid is baked in as squares if the `roll20-grid` setting is on, left out otherwise.
+    fn export_roll20(&mut self, ctx: &mut Context) -> GameResult<path::PathBuf> {
+        let (columns, rows, world) = self.snapped_view(ctx);
+        let (width, height) = Self::squares_size(columns, rows, Self::ROLL20_SQUARE)?;
+
+        // the grid layer is switched to baked squares for the render only
+        let (shape, visible) = (self.grid.shape(), self.grid.visible());
+        self.grid.set_shape(GridShape::Square);
+        self.grid.set_visible(self.config.roll20_grid());
+        self.grid.set_capped(false);
+        let background = graphics::Color::from(self.colors.1);
+        let image = self.render_world(ctx, world, width, height, background, |kind| {
+            kind != LayerKind::Ui
+        });
+        self.grid.set_shape(shape);
+        self.grid.set_visible(visible);
+        self.grid.set_capped(true);
+
+        let path = export::output_path("roll20", "png")?;
+        export::save_png(ctx, &image?, &path)?;
+        let note = format!(
+            "Roll20 page size: {columns} x {rows} units\n\
+             Grid: {} px per unit, {}\n\
+             Image: {width} x {height} px\n",
+            Self::ROLL20_SQUARE,
+            match self.config.roll20_grid() {
+                true => "baked into the image",
+                false => "not baked in, enable the page grid",
+            }
+        );
+        fs::write(path.with_extension("txt"), note)?;
+        Ok(path)
+    }
+
+    /// Writes the map at the configured export resolution to a timestamped PNG.
+    fn export_high_res(&mut self, ctx: &mut Context) -> GameResult<path::PathBuf> {
+        let (width, height) = self.config.export_size();
+        let options = self.config.export_options();
+        let image = self.render_offscreen(ctx, width, height, &options)?;
+        let path = export::output_path("map", "png")?;
+        export::save_png(ctx, &image, &path)?;
+        Ok(path)
+    }
+
+    /// Writes the view snapped to whole grid squares as a print-ready PDF,
+    /// each square `print-square` inches wide.
+    ///
+    /// Maps larger than the printable area are tiled across pages, left to right and top to bottom.
+    fn export_pdf(&mut self, ctx: &mut Context) -> GameResult<path::PathBuf> {
+        let (page_size, margin) = (self.config.print_page(), self.config.print_margin());
+        let square = self.config.print_square();
+        let pixels_per_square = ((square * self.config.print_dpi() as f32).round() as u32).max(1);
+
+        let (columns, rows, world) = self.snapped_view(ctx);
+        let (width, height) = Self::squares_size(columns, rows, pixels_per_square)?;
+        let background = graphics::Color::from(self.colors.1);
+        // every square is printed, however many fit on screen
+        self.grid.set_capped(false);
+        let image = self.render_world(ctx, world, width, height, background, |kind| {
+            kind != LayerKind::Ui
+        });
+        self.grid.set_capped(true);
+        let pixels = image?.to_pixels(ctx)?;
+
+        // whole squares that fit inside the margins of one page
+        let (page_width, page_height) = page_size.inches();
+        let fit = |length: f32| (((length - margin * 2.0) / square).floor() as u32).max(1);
+        let (page_columns, page_rows) = (fit(page_width), fit(page_height));
+
+        let mut pages = vec![];
+        for row in (0..rows).step_by(page_rows as usize) {
+            for column in (0..columns).step_by(page_columns as usize) {
+                let tile_columns = page_columns.min(columns - column);
+                let tile_rows = page_rows.min(rows - row);
+                let (x, y) = (column * pixels_per_square, row * pixels_per_square);
+                let (w, h) = (
+                    tile_columns * pixels_per_square,
+                    tile_rows * pixels_per_square,
+                );
+
+                let mut tile = Vec::with_capacity((w * h * 4) as usize);
+                for line in y..y + h {
+                    let start = ((line * width + x) * 4) as usize;
+                    tile.extend_from_slice(&pixels[start..start + (w * 4) as usize]);
+                }
+                pages.push(pdf::Page {
+                    width: w,
+                    height: h,
+                    pixels: tile,
+                    position: (margin, margin),
+                    size: (tile_columns as f32 * square, tile_rows as f32 * square),
+                });
+            }
+        }
+
+        let path = export::output_path("print", "pdf")?;
+        pdf::save(&pages, page_size, &path)?;
+        Ok(path)
+    }
+
+    /// Writes the map at the window size times the configured pixel scale to a timestamped PNG.
+    fn export_scaled(&mut self, ctx: &mut Context) -> GameResult<path::PathBuf> {
+        let (width, height) = ctx.gfx.drawable_size();
+        let options = self.config.export_options();
+        let size =
+            |length: f32| ((length * options.scale).round() as u32).clamp(1, export::MAX_SIZE);
+        let image = self.render_offscreen(ctx, size(width), size(height), &options)?;
+        let path = export::output_path("map", "png")?;
+        export::save_png(ctx, &image, &path)?;
+        Ok(path)
+    }
+
+    /// Rebuilds the legend from the layers present, `scale` is screen pixels per world unit.
+    fn update_legend(&mut self, scale: f32) {
+        let (fg, bg) = self.colors;
+        let mut entries = vec![
+            (Symbol::Swatch(fg), "Ink".to_string()),
+            (Symbol::Swatch(bg), "Paper".to_string()),
+        ];
+        if self.grid.visible() {
+            let cell = match self.grid.shape() {
+                GridShape::Square => "square",
+                GridShape::Hex => "hex",
+            };
+            entries.push((Symbol::Scale(self.grid.cell_size()), format!("1 {cell}")));
+        }
+        if self.lighting.visible() {
+            entries.push((Symbol::Swatch(Self::NIGHT), "Night".to_string()));
+        }
+        self.legend.set_entries(entries);
+        self.legend.set_scale(scale);
+    }
+
+    /// Writes the legend on its own as a PNG, at the current map zoom.
+    fn export_legend(&mut self, ctx: &mut Context) -> GameResult<path::PathBuf> {
+        self.update_legend(self.camera.zoom());
+        let size = self.legend.size(ctx)?.ceil();
+        let image = Image::new_canvas_image(
+            ctx,
+            ImageFormat::Rgba8UnormSrgb,
+            size.x as u32,
+            size.y as u32,
+            1,
+        );
+        let background = graphics::Color::from(self.colors.1);
+        let mut canvas = graphics::Canvas::from_image(ctx, image.clone(), background);
+        self.legend.draw_at(ctx, &mut canvas, Vec2::ZERO)?;
+        canvas.finish(ctx)?;
+
+        let path = export::output_path("legend", "png")?;
+        export::save_png(ctx, &image, &path)?;
+        Ok(path)
+    }
+
+    /// Writes the current palette as a GIMP or Adobe swatch file.
+    fn export_palette(&self, format: PaletteFormat) -> GameResult<path::PathBuf> {
+        let (fg, bg) = self.colors;
+        let swatches = [("Foreground", fg), ("Background", bg)];
+        let path = export::output_path("palette", format.extension())?;
+        match format {
+            PaletteFormat::Gpl => swatches::save_gpl("Cartographer", &swatches, &path)?,
+            PaletteFormat::Ase => swatches::save_ase(&swatches, &path)?,
+        }
+        Ok(path)
+    }
+
+    /// Renders the map at window size without UI layers and places it on the clipboard.
+    fn copy_map(&mut self, ctx: &mut Context) {
+        let (width, height) = ctx.gfx.drawable_size();
+        let (width, height) = (width as u32, height as u32);
+        let options = ExportOptions {
+            include_ui: false,
+            ..self.config.export_options()
+        };
+        let result = self
+            .render_offscreen(ctx, width, height, &options)
+            .and_then(|image| {
+                let pixels = image.to_pixels(ctx)?;
+                self.clipboard
+                    .set_image(image.width(), image.height(), pixels)
+                    .map_err(|e| ggez::GameError::CustomError(e.to_string()))
+            });
+        match result {
+            Ok(()) => self.log.push_in(
+                Category::Export,
+                Level::Info,
+                "Copied map image to clipboard".to_string(),
+            ),
+            Err(e) => self
+                .log
+                .push_in(Category::Export, Level::Error, format!("Copy failed: {e}")),
+        }
+    }
+
+    /// Logs the output path of an export, or why it failed.
+    fn report_export(&mut self, what: &str, result: GameResult<path::PathBuf>) {
+        match result {
+            Ok(path) => {
+                self.log.push_rich(
+                    Category::Export,
+                    Level::Info,
+                    vec![
+                        Span::plain(format!("Exported {what} to ")),
+                        Span::accent(path.display().to_string()),
+                    ],
+                );
+                self.sync(&path);
+            }
+            Err(e) => self.log.push_in(
+                Category::Export,
+                Level::Error,
+                format!("Export failed: {e}"),
+            ),
+        }
+    }
+
+    /// Copies an exported file into the configured sync directory, if any.
+    fn sync(&mut self, path: &path::Path) {
+        let Some(dir) = self.config.sync_dir() else {
+            return;
+        };
+        let time = DateTime::now().clock();
+        match export::mirror(path, dir) {
+            Ok(copy) => {
+                self.sync_status = Some(format!("synced {time}"));
+                self.log.push_rich(
+                    Category::Export,
+                    Level::Info,
+                    vec![
+                        Span::plain("Synced to "),
+                        Span::accent(copy.display().to_string()),
+                    ],
+                );
+            }
+            Err(e) => {
+                self.sync_status = Some(format!("sync failed {time}"));
+                self.log
+                    .push_in(Category::Export, Level::Error, format!("Sync failed: {e}"));
+            }
+        }
+    }
+
+    /// Collects the settings of every part of the scene for saving.
+    fn snapshot(&self) -> MapState {
+        MapState {
+            version: MapState::VERSION,
+            camera: Some(CameraState {
+                translation: self.camera.translation().to_array(),
+                zoom: self.camera.zoom(),
+            }),
+            colors: Some(self.colors),
+            grid: Some(GridState {
+                shape: self.grid.shape(),
+                cell_size: self.grid.cell_size(),
+                opacity: self.grid.opacity(),
+                visible: self.grid.visible(),
+            }),
+            lighting: Some(LightingState {
+                time: self.lighting.time(),
+                speed: self.lighting.speed(),
+                visible: self.lighting.visible(),
+            }),
+            cartouche: Some(CartoucheState {
+                title: self.cartouche.title().to_string(),
+                dedication: self.cartouche.dedication().to_string(),
+                date: self.cartouche.date().to_string(),
+                visible: self.cartouche.visible(),
+            }),
+            effects: Effect::ALL
+                .into_iter()
+                .map(|effect| EffectState {
+                    effect,
+                    enabled: self.post.enabled(effect),
+                    strength: self.post.strength(effect),
+                })
+                .collect(),
+        }
+    }
+
+    /// Applies a saved [`MapState`] to the scene, missing sections are left as they are.
+    ///
+    /// Sections of locked layers are skipped with a warning.
+    fn restore(&mut self, ctx: &mut Context, state: MapState) -> GameResult {
+        if let Some(camera) = state.camera {
+            self.camera
+                .set_view(Vec2::from_array(camera.translation), camera.zoom);
+        }
+
+        if let Some((fg, bg)) = state.colors {
+            if self.recolorable() {
+                self.transition = None;
+                self.set_colors(ctx, fg, bg)?;
+            }
+        }
+
+        if let Some(grid) = state.grid.filter(|_| self.editable("grid")) {
+            self.grid.set_shape(grid.shape);
+            self.grid.set_cell_size(grid.cell_size);
+            self.grid.set_opacity(grid.opacity);
+            self.grid.set_visible(grid.visible);
+        }
+
+        if let Some(lighting) = state.lighting.filter(|_| self.editable("lighting")) {
+            self.lighting.set_time(lighting.time);
+            self.lighting.set_speed(lighting.speed);
+            self.lighting.set_visible(lighting.visible);
+            // a saved clock stays paused under reduced motion
+            if self.config.reduced_motion() {
+                self.lighting.set_speed(0.0);
+            }
+        }
+
+        if let Some(cartouche) = state.cartouche.filter(|_| self.editable("cartouche")) {
+            self.cartouche.set_title(cartouche.title);
+            self.cartouche.set_dedication(cartouche.dedication);
+            self.cartouche.set_date(cartouche.date);
+            self.cartouche.set_visible(cartouche.visible);
+        }
+
+        for effect in state.effects {
+            self.post.set_enabled(effect.effect, effect.enabled);
+            self.post.set_strength(effect.effect, effect.strength);
+        }
+        Ok(())
+    }
+
+    fn save_map(&mut self) {
+        let path = self.config.save_file().to_path_buf();
+        match save::save(&self.snapshot(), &path) {
+            Ok(()) => {
+                self.log.push_rich(
+                    Category::Export,
+                    Level::Info,
+                    vec![
+                        Span::plain("Saved map to "),
+                        Span::accent(path.display().to_string()),
+                    ],
+                );
+                self.sync(&path);
+            }
+            Err(e) => self
+                .log
+                .push_in(Category::Export, Level::Error, format!("Save failed: {e}")),
+        }
+    }
+
+    fn load_map(&mut self, ctx: &mut Context) -> GameResult {
+        let path = self.config.save_file().to_path_buf();
+        match save::load(&path) {
+            Ok((state, warnings)) => {
+                self.restore(ctx, state)?;
+                for warning in warnings {
+                    self.log.push_in(Category::Export, Level::Warn, warning);
+                }
+                self.log.push_rich(
+                    Category::Export,
+                    Level::Info,
+                    vec![
+                        Span::plain("Loaded map from "),
+                        Span::accent(path.display().to_string()),
+                    ],
+                );
+            }
+            Err(e) => self
+                .log
+                .push_in(Category::Export, Level::Error, format!("Load failed: {e}")),
+        }
+        Ok(())
+    }
+
+    fn toggle_effect(&mut self, effect: Effect) {
+        let enabled = !self.post.enabled(effect);
+        self.post.set_enabled(effect, enabled);
+        self.log.push_in(
+            Category::Input,
+            Level::Info,
+            format!(
+                "{effect:?} {}",
+                if enabled { "enabled" } else { "disabled" }
+            ),
+        );
+    }
+
+    /// Handles editing keys while typing into the cartouche.
+    ///
+    /// Tab moves to the next line, Return or Escape finish editing.
+    fn cartouche_key(&mut self, keycode: ggez::input::keyboard::KeyCode, line: Line) {
+        use ggez::input::keyboard::KeyCode;
+        match keycode {
+            KeyCode::Back => self.cartouche.pop(),
+            KeyCode::Tab => self.cartouche.set_editing(Some(line.next())),
+            KeyCode::Return | KeyCode::NumpadEnter | KeyCode::Escape => {
+                self.cartouche.set_editing(None)
+            }
+            _ => (),
+        }
+    }
+
+    /// Handles a key press while the log search query is being typed.
+    ///
+    /// Return keeps the query and stops typing, Escape clears it.
+    fn search_key(&mut self, keycode: ggez::input::keyboard::KeyCode) {
+        use ggez::input::keyboard::KeyCode;
+        match keycode {
+            KeyCode::Back => {
+                let mut query = self.log.query().unwrap_or_default().to_string();
+                query.pop();
+                self.log.set_query(Some(query));
+                self.log.first_match();
+            }
+            KeyCode::Return | KeyCode::NumpadEnter => self.typing = false,
+            KeyCode::Escape => {
+                self.typing = false;
+                self.log.set_query(None);
+            }
+            _ => (),
+        }
+    }
+
+    /// Copies the log entry under the mouse cursor to the system clipboard.
+    ///
+    /// With the log focused from the keyboard, the newest entry on screen is copied instead.
+    fn copy_hovered(&mut self, ctx: &Context) {
+        let position = ctx.mouse.position();
+        let message = match self.log.focused() {
+            true => self.log.top_entry(),
+            false => self.log.entry_at(ctx, Vec2::new(position.x, position.y)),
+        };
+        let Some(message) = message else {
+            return;
+        };
+        match self.clipboard.set_text(message) {
+            Ok(()) => self.log.push_in(
+                Category::Input,
+                Level::Info,
+                "Copied to clipboard".to_string(),
+            ),
+            Err(e) => self
+                .log
+                .push_in(Category::Input, Level::Error, format!("Copy failed: {e}")),
+        }
+    }
+
+    fn toggle_category(&mut self, category: Category) {
+        let shown = !self.log.shown(category);
+        self.log.set_shown(category, shown);
+    }
+
+    /// Outlines the selected layer in the accent color, after the first Tab press.
+    ///
+    /// Hidden layers are not outlined. The outline stays inside the canvas,
+    /// so layers covering the whole map get a frame along its edges.
+    fn draw_focus(&mut self, ctx: &mut Context, canvas: &mut graphics::Canvas) -> GameResult {
+        if !self.show_focus {
+            return Ok(());
+        }
+        let color = Tone::Accent.color(self.colors.0, Level::Info);
+        let name = self.selected_layer();
+        let area = match self
+            .layers()
+            .into_iter()
+            .find(|layer| layer.name() == name && layer.visible())
+        {
+            Some(layer) => layer.focus_bounds(ctx, canvas)?,
+            None => return Ok(()),
+        };
+
+        let screen = canvas_bounds(ctx, canvas);
+        let (gap, stroke) = (Self::FOCUS_GAP, Self::STANDARD_LINE);
+        let left = (area.left() - gap).max(screen.left() + stroke);
+        let top = (area.top() - gap).max(screen.top() + stroke);
+        let right = (area.right() + gap).min(screen.right() - stroke);
+        let bottom = (area.bottom() + gap).min(screen.bottom() - stroke);
+        let outline = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::stroke(stroke),
+            graphics::Rect::new(left, top, right - left, bottom - top),
+            graphics::Color::from(color),
+        )?;
+        canvas.draw(&outline, graphics::DrawParam::default());
+        Ok(())
+    }
+
+    /// Draws every visible layer accepted by `filter` onto the canvas, bottom to top.
+    ///
+    /// World space layers are drawn with `world` as the visible area, screen space layers with `screen`.
+    /// While a layer is soloed, the other layers are skipped except for [`LayerKind::Ui`].
+    fn draw_scene(
+        &mut self,
+        ctx: &mut Context,
+        canvas: &mut graphics::Canvas,
+        world: graphics::Rect,
+        screen: graphics::Rect,
+        filter: impl Fn(LayerKind) -> bool,
+    ) -> GameResult {
+        self.update_legend(screen.w / world.w);
+        let solo = self.solo.clone();
+        let mut layers = self.layers();
+        layers.sort_by_key(|layer| layer.kind());
+        for layer in layers.into_iter().filter(|layer| {
+            let soloed = match &solo {
+                Some(name) => layer.name() == name || layer.kind() == LayerKind::Ui,
+                None => true,
+            };
+            layer.visible() && soloed && filter(layer.kind())
+        }) {
+            match layer.kind().is_world_space() {
+                true => canvas.set_screen_coordinates(world),
+                false => canvas.set_screen_coordinates(screen),
+            }
+            layer.draw(ctx, canvas)?;
+        }
+        Ok(())
     }
 }
 
 impl EventHandler for Cartographer {
     fn draw(&mut self, ctx: &mut Context) -> Result<(), ggez::GameError> {
-        let mut canvas =
-            graphics::Canvas::from_frame(ctx, graphics::Color::from(*self.palette.bg()));
+        // the scene is only re-rendered when something changed
+        if self.dirty {
+            let world = self.camera.view(ctx);
+            let (width, height) = ctx.gfx.drawable_size();
+            let screen = graphics::Rect::new(0.0, 0.0, width, height);
 
-        // draw the log box
-        let box_offset = self.log.set_box_position(ctx, (Self::BORDER, Self::BORDER));
-        let text_offset = box_offset + ggez::glam::Vec2::new(5.0, 2.0);
-        let (log_text, log_box) = (self.log.text(), &self.log.mesh());
+            let background = graphics::Color::from(self.colors.1);
+            let mut canvas = match &mut self.msaa {
+                Some(msaa) => {
+                    let (msaa, scene) = (msaa.image(ctx), self.scene.image(ctx));
+                    graphics::Canvas::from_msaa(ctx, msaa, scene, background)
+                }
+                None => graphics::Canvas::from_screen_image(ctx, &mut self.scene, background),
+            };
+            self.draw_scene(ctx, &mut canvas, world, screen, |kind| {
+                kind != LayerKind::Ui
+            })?;
+            canvas.finish(ctx)?;
 
-        canvas.draw(&log_text, graphics::DrawParam::default().dest(text_offset));
-        canvas.draw(log_box, graphics::DrawParam::default().dest(box_offset));
+            // post effects only apply to the map, the UI is drawn on top
+            let map = self.post.apply(ctx, self.scene.image(ctx))?;
+            let mut canvas = graphics::Canvas::from_screen_image(
+                ctx,
+                &mut self.frame,
+                graphics::Color::from(self.colors.1),
+            );
+            canvas.draw(&map, graphics::DrawParam::default());
+            self.draw_scene(ctx, &mut canvas, world, screen, |kind| {
+                kind == LayerKind::Ui
+            })?;
+            self.draw_focus(ctx, &mut canvas)?;
+            canvas.finish(ctx)?;
+            self.dirty = false;
+        }
 
+        let mut canvas = graphics::Canvas::from_frame(ctx, graphics::Color::from(self.colors.1));
+        canvas.draw(&self.frame.image(ctx), graphics::DrawParam::default());
         canvas.finish(ctx)?;
+
+        if self.screenshot {
+            self.screenshot = false;
+            match self.save_screenshot(ctx) {
+                Ok(path) => {
+                    self.log.push_rich(
+                        Category::Export,
+                        Level::Info,
+                        vec![
+                            Span::plain("Saved screenshot to "),
+                            Span::accent(path.display().to_string()),
+                        ],
+                    );
+                    self.sync(&path);
+                }
+                Err(e) => self.log.push_in(
+                    Category::Export,
+                    Level::Error,
+                    format!("Screenshot failed: {e}"),
+                ),
+            }
+            self.dirty = true;
+        }
         Ok(())
     }
 
     fn update(&mut self, ctx: &mut Context) -> Result<(), ggez::GameError> {
-        ctx.gfx
-            .set_window_title(format!("Cartographer - FPS {}", ctx.time.fps().round()).as_str());
+        let dt = ctx.time.delta().as_secs_f32();
+        if self.log.drain() {
+            self.dirty = true;
+        }
+        self.particles.update(dt);
+        self.lighting.update(dt);
+        let lighting = self.lighting.visible() && self.lighting.speed() != 0.0;
+        // moving grain needs a new frame every tick
+        let grain = self.post.enabled(Effect::Grain) && self.post.animated();
+        if !self.particles.is_empty() || lighting || grain {
+            self.dirty = true;
+        }
+        if let Some(transition) = &mut self.transition {
+            transition.update(dt);
+            let (fg, bg) = transition.colors();
+            if transition.is_done() {
+                self.transition = None;
+            }
+            self.set_colors(ctx, fg, bg)?;
+            self.dirty = true;
+        }
+        let mut title = format!("Cartographer - FPS {}", ctx.time.fps().round());
+        if let Some(status) = &self.sync_status {
+            title += &format!(" - {status}");
+        }
+        ctx.gfx.set_window_title(&title);
         Ok(())
     }
 
@@ -86,18 +1109,257 @@ impl EventHandler for Cartographer {
         _repeated: bool,
     ) -> Result<(), ggez::GameError> {
         use ggez::input::keyboard::{KeyCode, KeyMods};
+        self.dirty = true;
+        // typed characters arrive through `text_input_event`
+        if self.typing {
+            if let Some(keycode) = input.keycode {
+                self.search_key(keycode);
+            }
+            return Ok(());
+        }
+        if let Some(line) = self.cartouche.editing() {
+            if let Some(keycode) = input.keycode {
+                self.cartouche_key(keycode, line);
+            }
+            return Ok(());
+        }
         match input.keycode {
+            Some(KeyCode::S) if input.mods.contains(KeyMods::CTRL) => self.save_map(),
+            Some(KeyCode::O) if input.mods.contains(KeyMods::CTRL) => self.load_map(ctx)?,
             Some(KeyCode::S) => self.log.decr_offset(),
             Some(KeyCode::W) => self.log.incr_offset(),
             Some(KeyCode::A) => {
-                self.log
-                    .push(format!("Pushed String No: {}", self.counter + 1));
+                self.log.push_in(
+                    Category::Input,
+                    Level::Info,
+                    format!("Pushed String No: {}", self.counter + 1),
+                );
                 self.counter += 1;
             }
+            // the focused log box moves with the arrow keys and resizes with Shift
+            Some(KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right)
+                if self.log.focused() =>
+            {
+                let step = match input.keycode {
+                    Some(KeyCode::Up) => Vec2::new(0.0, -Self::LOG_STEP),
+                    Some(KeyCode::Down) => Vec2::new(0.0, Self::LOG_STEP),
+                    Some(KeyCode::Left) => Vec2::new(-Self::LOG_STEP, 0.0),
+                    _ => Vec2::new(Self::LOG_STEP, 0.0),
+                };
+                if self.editable("log") {
+                    match input.mods.contains(KeyMods::SHIFT) {
+                        true => self.log.resize_by(ctx, step)?,
+                        false => self.log.move_by(step),
+                    }
+                }
+            }
+            Some(KeyCode::Up) => self.camera.pan(Vec2::new(0.0, -Self::PAN_STEP)),
+            Some(KeyCode::Down) => self.camera.pan(Vec2::new(0.0, Self::PAN_STEP)),
+            Some(KeyCode::Left) => self.camera.pan(Vec2::new(-Self::PAN_STEP, 0.0)),
+            Some(KeyCode::Right) => self.camera.pan(Vec2::new(Self::PAN_STEP, 0.0)),
+            Some(KeyCode::Equals | KeyCode::NumpadAdd) => {
+                let (width, height) = ctx.gfx.drawable_size();
+                self.camera
+                    .zoom_at(Self::ZOOM_STEP, Vec2::new(width, height) / 2.0);
+            }
+            Some(KeyCode::Minus | KeyCode::NumpadSubtract) => {
+                let (width, height) = ctx.gfx.drawable_size();
+                self.camera
+                    .zoom_at(Self::ZOOM_STEP.recip(), Vec2::new(width, height) / 2.0);
+            }
+            Some(KeyCode::G) => {
+                if self.editable("grid") {
+                    self.grid.set_visible(!self.grid.visible());
+                }
+            }
+            Some(KeyCode::H) => {
+                if self.editable("grid") {
+                    match self.grid.shape() {
+                        GridShape::Square => self.grid.set_shape(GridShape::Hex),
+                        GridShape::Hex => self.grid.set_shape(GridShape::Square),
+                    }
+                }
+            }
+            Some(KeyCode::LBracket) => {
+                if self.editable("grid") {
+                    self.grid.set_cell_size(self.grid.cell_size() / 2.0);
+                }
+            }
+            Some(KeyCode::RBracket) => {
+                if self.editable("grid") {
+                    self.grid.set_cell_size(self.grid.cell_size() * 2.0);
+                }
+            }
+            Some(KeyCode::Comma) => {
+                if self.editable("grid") {
+                    self.grid.set_opacity(self.grid.opacity() - 0.1);
+                }
+            }
+            Some(KeyCode::Period) => {
+                if self.editable("grid") {
+                    self.grid.set_opacity(self.grid.opacity() + 0.1);
+                }
+            }
             Some(KeyCode::N) => {
+                if !self.recolorable() {
+                    return Ok(());
+                }
                 self.palette = colors::Palette::random(self.seed.deref_mut(), 1.0, 1.0);
-                self.log.color_mut(ctx, *self.palette.fg())?;
+                self.transition = Some(PaletteTransition::new(
+                    self.colors,
+                    (*self.palette.fg(), *self.palette.bg()),
+                    match self.config.reduced_motion() {
+                        true => 0.0,
+                        false => Self::PALETTE_FADE,
+                    },
+                ));
+                let hex = |color: [f32; 4]| {
+                    let [r, g, b] =
+                        [0, 1, 2].map(|i| (color[i].clamp(0.0, 1.0) * 255.0).round() as u8);
+                    format!("#{r:02x}{g:02x}{b:02x}")
+                };
+                let (fg, bg) = (*self.palette.fg(), *self.palette.bg());
+                self.log.push_rich(
+                    Category::Generation,
+                    Level::Info,
+                    vec![
+                        Span::plain("New palette: "),
+                        Span::muted("ink "),
+                        Span::new(hex(fg), Tone::Color(fg)),
+                        Span::muted(", paper "),
+                        Span::plain(hex(bg)),
+                    ],
+                );
+            }
+            Some(KeyCode::P) if input.mods.contains(KeyMods::CTRL) => {
+                let result = self.export_pdf(ctx);
+                self.report_export("map", result);
+            }
+            Some(KeyCode::P) => {
+                if self.parchment.is_none() {
+                    self.log.warn(format!("{} not found", Self::PARCHMENT));
+                } else if self.editable("parchment") {
+                    if let Some(parchment) = &mut self.parchment {
+                        parchment.set_visible(!parchment.visible());
+                    }
+                }
+            }
+            Some(KeyCode::L) => {
+                if self.editable("lighting") {
+                    self.lighting.set_visible(!self.lighting.visible());
+                }
+            }
+            Some(KeyCode::K) if self.config.reduced_motion() => self.log.push_in(
+                Category::Input,
+                Level::Warn,
+                "The day/night clock stays paused while reduced motion is on".to_string(),
+            ),
+            Some(KeyCode::K) => {
+                if self.editable("lighting") {
+                    match self.lighting.speed() == 0.0 {
+                        // a full day passes in DAY_LENGTH seconds
+                        true => self.lighting.set_speed(24.0 / Self::DAY_LENGTH),
+                        false => self.lighting.set_speed(0.0),
+                    }
+                }
+            }
+            Some(KeyCode::T) if input.mods.contains(KeyMods::CTRL) => {
+                if self.editable("cartouche") {
+                    self.cartouche.set_visible(true);
+                    self.cartouche.set_editing(Some(Line::Title));
+                    self.log.push_in(
+                        Category::Input,
+                        Level::Info,
+                        "Editing cartouche, Tab for the next line, Return to finish".to_string(),
+                    );
+                }
+            }
+            Some(KeyCode::T) => {
+                if self.editable("cartouche") {
+                    match input.mods.contains(KeyMods::SHIFT) {
+                        true => {
+                            let style = self.config.cartouche_style();
+                            self.cartouche.reroll(self.seed.deref_mut(), style);
+                            self.cartouche.set_visible(true);
+                        }
+                        false => self.cartouche.set_visible(!self.cartouche.visible()),
+                    }
+                }
+            }
+            Some(KeyCode::F12) if input.mods.contains(KeyMods::SHIFT) => {
+                let result = self.export_high_res(ctx);
+                self.report_export("map", result);
+            }
+            Some(KeyCode::R) => {
+                let result = self.export_roll20(ctx);
+                // the page size note is mirrored along with the image
+                let note = result.as_ref().ok().map(|path| path.with_extension("txt"));
+                self.report_export("map", result);
+                if let Some(note) = note {
+                    self.sync(&note);
+                }
+            }
+            Some(KeyCode::M) if input.mods.contains(KeyMods::SHIFT) => {
+                let result = self.export_legend(ctx);
+                self.report_export("legend", result);
             }
+            Some(KeyCode::M) => {
+                if self.editable("legend") {
+                    self.legend.set_visible(!self.legend.visible());
+                }
+            }
+            Some(KeyCode::E) if input.mods.contains(KeyMods::SHIFT) => {
+                for format in PaletteFormat::ALL {
+                    let result = self.export_palette(format);
+                    self.report_export("palette", result);
+                }
+            }
+            Some(KeyCode::E) => {
+                let result = self.export_scaled(ctx);
+                self.report_export("map", result);
+            }
+            Some(KeyCode::F12) => self.screenshot = true,
+            // with a search query, Return steps to older matches and Shift+Return to newer ones
+            Some(KeyCode::Return) if self.log.query().is_some() => {
+                match input.mods.contains(KeyMods::SHIFT) {
+                    true => self.log.prev_match(),
+                    false => self.log.next_match(),
+                };
+            }
+            Some(KeyCode::Escape) => self.log.set_query(None),
+            Some(KeyCode::Tab) => {
+                self.show_focus = true;
+                self.selected = (self.selected + 1) % self.layer_names().len();
+                let name = self.selected_layer();
+                self.log.set_focused(name == self.log.name());
+                self.log
+                    .push_in(Category::Input, Level::Info, format!("Selected {name}"));
+            }
+            Some(KeyCode::Y) => self.copy_hovered(ctx),
+            Some(KeyCode::Grave) => self.log.set_collapsed(!self.log.collapsed()),
+            Some(KeyCode::F5) => self.toggle_lock(),
+            Some(KeyCode::F7) => {
+                let saved = self.config.set_large_text(!self.config.large_text());
+                self.apply_text_size(ctx)?;
+                self.report_setting(saved);
+            }
+            Some(KeyCode::F8) => {
+                let saved = self
+                    .config
+                    .set_reduced_motion(!self.config.reduced_motion());
+                self.apply_motion();
+                self.report_setting(saved);
+            }
+            Some(KeyCode::F6) => self.toggle_solo(),
+            // number keys toggle the log categories, in header order
+            Some(KeyCode::Key1) => self.toggle_category(Category::Generation),
+            Some(KeyCode::Key2) => self.toggle_category(Category::Input),
+            Some(KeyCode::Key3) => self.toggle_category(Category::Export),
+            Some(KeyCode::Key4) => self.toggle_category(Category::System),
+            Some(KeyCode::F1) => self.toggle_effect(Effect::Sepia),
+            Some(KeyCode::F2) => self.toggle_effect(Effect::Grain),
+            Some(KeyCode::F3) => self.toggle_effect(Effect::Vignette),
+            Some(KeyCode::C) if input.mods.contains(KeyMods::SHIFT) => self.copy_map(ctx),
             Some(KeyCode::C) => {
                 if input.mods.contains(KeyMods::CTRL) {
                     println!("terminating!");
@@ -108,6 +1370,131 @@ impl EventHandler for Cartographer {
         }
         Ok(())
     }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) -> GameResult {
+        if self.cartouche.editing().is_some() {
+            if !character.is_control() {
+                self.cartouche.push(character);
+                self.dirty = true;
+            }
+            return Ok(());
+        }
+        // `/` opens the log search, the following characters make up the query
+        if !self.typing {
+            if character == '/' {
+                self.typing = true;
+                self.log.set_query(Some(String::new()));
+                self.dirty = true;
+            }
+            return Ok(());
+        }
+        if !character.is_control() {
+            let mut query = self.log.query().unwrap_or_default().to_string();
+            query.push(character);
+            self.log.set_query(Some(query));
+            self.log.first_match();
+            self.dirty = true;
+        }
+        Ok(())
+    }
+
+    fn mouse_wheel_event(&mut self, ctx: &mut Context, _x: f32, y: f32) -> GameResult {
+        let position = ctx.mouse.position();
+        let position = Vec2::new(position.x, position.y);
+        self.dirty = true;
+
+        // scrolling over the log moves through the messages, anywhere else zooms the map
+        if self.log.visible() && self.log.contains(ctx, position) {
+            for _ in 0..y.abs().ceil() as u32 {
+                match y > 0.0 {
+                    true => self.log.incr_offset(),
+                    false => self.log.decr_offset(),
+                }
+            }
+        } else {
+            self.camera.zoom_at(Self::ZOOM_STEP.powf(y), position);
+        }
+        Ok(())
+    }
+
+    fn mouse_motion_event(
+        &mut self,
+        ctx: &mut Context,
+        _x: f32,
+        _y: f32,
+        dx: f32,
+        dy: f32,
+    ) -> GameResult {
+        // dragging with the middle button moves the map along with the cursor
+        if ctx.mouse.button_pressed(MouseButton::Middle) {
+            self.camera.pan(-Vec2::new(dx, dy));
+            self.dirty = true;
+        }
+        match self.drag {
+            Some(Handle::Border) => self.log.move_by(Vec2::new(dx, dy)),
+            Some(Handle::Corner) => self.log.resize_by(ctx, Vec2::new(dx, dy))?,
+            None => return Ok(()),
+        }
+        self.dirty = true;
+        Ok(())
+    }
+
+    fn mouse_button_down_event(
+        &mut self,
+        ctx: &mut Context,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) -> GameResult {
+        let point = Vec2::new(x, y);
+        let over_log = self.log.visible() && self.log.contains(ctx, point);
+        match button {
+            // the log border and corner start a drag, clicks on the map send out a ping
+            MouseButton::Left => {
+                let handle = match self.log.visible() {
+                    true => self.log.handle_at(ctx, point),
+                    false => None,
+                };
+                match handle {
+                    Some(_) => {
+                        if self.editable("log") {
+                            self.drag = handle;
+                        }
+                    }
+                    None if !over_log => {
+                        self.ping(point);
+                        self.dirty = true;
+                    }
+                    None => (),
+                }
+            }
+            MouseButton::Right if self.log.visible() => {
+                self.copy_hovered(ctx);
+                self.dirty = true;
+            }
+            _ => (),
+        }
+        Ok(())
+    }
+
+    fn mouse_button_up_event(
+        &mut self,
+        _ctx: &mut Context,
+        button: MouseButton,
+        _x: f32,
+        _y: f32,
+    ) -> GameResult {
+        if button == MouseButton::Left {
+            self.drag = None;
+        }
+        Ok(())
+    }
+
+    fn resize_event(&mut self, _ctx: &mut Context, _width: f32, _height: f32) -> GameResult {
+        // the cached scene is resized and cleared along with the window
+        self.dirty = true;
+        Ok(())
+    }
 }
 fn main() -> GameResult {
     let resource_dir = env::var("CARGO_MANIFEST_DIR").map_or_else(
@@ -115,18 +1502,16 @@ fn main() -> GameResult {
         |path| path::PathBuf::from(path + "/resources"),
     );
 
-    let window_setup = WindowSetup::default().title("Cartographer");
-
-    let window_mode = WindowMode::default().dimensions(1280.0, 720.0);
+    let config = Config::from_args(env::args().skip(1))?;
 
     let cb = ggez::ContextBuilder::new("meshbatch", "ggez")
         .add_resource_path(resource_dir)
-        .window_setup(window_setup)
-        .window_mode(window_mode);
+        .window_setup(config.window_setup("Cartographer"))
+        .window_mode(config.window_mode());
 
     let (mut ctx, event_loop) = cb.build()?;
 
-    let state = Cartographer::new(&mut ctx)?;
+    let state = Cartographer::new(&mut ctx, config)?;
 
     event::run(ctx, event_loop, state)
 }
@@ -0,0 +1,100 @@
+use ggez::{
+    context::Has,
+    glam::Vec2,
+    graphics::{GraphicsContext, Rect},
+};
+
+/// View transform applied to the map layers.
+///
+/// UI elements such as the [`Log`](super::logger::Log) are drawn in screen space
+/// and are not affected by the camera.
+#[derive(Debug)]
+pub struct Camera {
+    translation: Vec2,
+    zoom: f32,
+}
+
+impl Camera {
+    const MIN_ZOOM: f32 = 0.1;
+    const MAX_ZOOM: f32 = 10.0;
+
+    /// Creates a new [`Camera`] at the world origin with no zoom.
+    pub fn new() -> Camera {
+        Self {
+            translation: Vec2::ZERO,
+            zoom: 1.0,
+        }
+    }
+
+    /// Returns the world position shown at the top-left corner of the screen.
+    pub fn translation(&self) -> Vec2 {
+        self.translation
+    }
+
+    /// Returns the current zoom factor.
+    pub fn zoom(&self) -> f32 {
+        self.zoom
+    }
+
+    /// Jumps to the given view, e.g. when loading a saved map.
+    ///
+    /// `translation` is the world position at the top-left corner of the screen.
+    pub fn set_view(&mut self, translation: Vec2, zoom: f32) {
+        self.translation = translation;
+        self.zoom = zoom.clamp(Self::MIN_ZOOM, Self::MAX_ZOOM);
+    }
+
+    /// Moves the view by `delta` screen pixels.
+    pub fn pan(&mut self, delta: Vec2) {
+        self.translation += delta / self.zoom;
+    }
+
+    /// Multiplies the zoom by `factor`, keeping the world point under `anchor` fixed.
+    ///
+    /// `anchor` is given in screen coordinates, e.g. the mouse position.
+    pub fn zoom_at(&mut self, factor: f32, anchor: Vec2) {
+        let before = self.screen_to_world(anchor);
+        self.zoom = (self.zoom * factor).clamp(Self::MIN_ZOOM, Self::MAX_ZOOM);
+        self.translation = before - anchor / self.zoom;
+    }
+
+    /// Converts a screen position into world coordinates.
+    pub fn screen_to_world(&self, point: Vec2) -> Vec2 {
+        self.translation + point / self.zoom
+    }
+
+    /// Returns the visible area of the world.
+    ///
+    /// Intended to be passed to [`Canvas::set_screen_coordinates`](ggez::graphics::Canvas::set_screen_coordinates)
+    /// before drawing the map layers.
+    pub fn view(&self, ctx: &impl Has<GraphicsContext>) -> Rect {
+        let (width, height) = ctx.retrieve().drawable_size();
+        Rect::new(
+            self.translation.x,
+            self.translation.y,
+            width / self.zoom,
+            height / self.zoom,
+        )
+    }
+
+    /// Returns the visible area of the world, grown around its center to match `aspect` (width / height).
+    ///
+    /// Used when rendering the view into an image whose shape differs from the window.
+    pub fn view_fit(&self, ctx: &impl Has<GraphicsContext>, aspect: f32) -> Rect {
+        let mut view = self.view(ctx);
+        let center = view.center();
+        if view.w / view.h < aspect {
+            view.w = view.h * aspect;
+        } else {
+            view.h = view.w / aspect;
+        }
+        view.move_to(Vec2::new(center.x - view.w / 2.0, center.y - view.h / 2.0));
+        view
+    }
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
+}
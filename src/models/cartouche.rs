@@ -0,0 +1,384 @@
+use std::str::FromStr;
+
+use ggez::{
+    glam::Vec2,
+    graphics::{self, Canvas, DrawParam, Mesh, Text, TextFragment},
+    Context, GameResult,
+};
+use rand::{seq::SliceRandom, Rng};
+
+use super::{
+    layers::{canvas_bounds, Layer, LayerKind},
+    logger::TextParams,
+};
+
+/// Wording used for the generated cartouche text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    /// Latin dedication with the year in roman numerals.
+    Latin,
+    /// English dedication with the year in digits.
+    Plain,
+}
+
+impl FromStr for Style {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "latin" => Ok(Style::Latin),
+            "plain" => Ok(Style::Plain),
+            _ => Err(format!("unknown cartouche style: {s}")),
+        }
+    }
+}
+
+/// A line of the cartouche text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line {
+    Title,
+    Dedication,
+    Date,
+}
+
+impl Line {
+    /// Returns the line below, wrapping around to the title.
+    pub fn next(&self) -> Line {
+        match self {
+            Line::Title => Line::Dedication,
+            Line::Dedication => Line::Date,
+            Line::Date => Line::Title,
+        }
+    }
+}
+
+/// Title box with a dedication and date, drawn in a corner of the map.
+#[derive(Debug)]
+pub struct Cartouche {
+    title: String,
+    dedication: String,
+    date: String,
+    params: TextParams,
+    margin: f32,
+    visible: bool,
+    /// Line being typed into, drawn with a caret.
+    editing: Option<Line>,
+    /// Cached frame, rebuilt when the text or color changes.
+    mesh: Option<Mesh>,
+}
+
+impl Cartouche {
+    const PADDING: f32 = 12.0;
+    const TITLE_SCALE: f32 = 1.5;
+
+    const LATIN_TITLES: [&'static str; 4] = ["Tabula", "Carta", "Mappa", "Descriptio"];
+    const LATIN_SUBJECTS: [&'static str; 5] = [
+        "Terrarum Incognitarum",
+        "Regionum Septentrionalium",
+        "Insularum Occultarum",
+        "Provinciarum Obscurarum",
+        "Orbis Nocturni",
+    ];
+    const LATIN_PATRONS: [&'static str; 5] =
+        ["Aurelio", "Casimiro", "Leopoldo", "Octaviano", "Severino"];
+    const PLAIN_SUBJECTS: [&'static str; 5] = [
+        "the Unknown Lands",
+        "the Northern Reaches",
+        "the Hidden Isles",
+        "the Shadowed Provinces",
+        "the Nocturnal World",
+    ];
+    const PLAIN_PATRONS: [&'static str; 5] = [
+        "His Grace the Duke of Aurel",
+        "the Margrave Casimir",
+        "Her Majesty Queen Leopolda",
+        "the Worshipful Company of Navigators",
+        "Lord Severin of the Eastern March",
+    ];
+
+    /// Creates a new [`Cartouche`] with seeded default text.
+    /// Parameters:
+    /// - `rng`: source of the default text, pass the map seed for reproducible text
+    /// - `style`: wording of the default text
+    /// - `params`: text appearance, the stroke width is used for the frame
+    /// - `margin`: distance from the top-right corner of the screen
+    pub fn generate(
+        rng: &mut impl Rng,
+        style: Style,
+        params: TextParams,
+        margin: f32,
+    ) -> Cartouche {
+        let mut cartouche = Self {
+            title: String::new(),
+            dedication: String::new(),
+            date: String::new(),
+            params,
+            margin,
+            visible: false,
+            editing: None,
+            mesh: None,
+        };
+        cartouche.reroll(rng, style);
+        cartouche
+    }
+
+    /// Replaces all lines with newly generated text.
+    pub fn reroll(&mut self, rng: &mut impl Rng, style: Style) {
+        let year = rng.gen_range(1450..1800);
+        let (title, dedication, date) = match style {
+            Style::Latin => (
+                format!(
+                    "{} {}",
+                    Self::LATIN_TITLES.choose(rng).unwrap(),
+                    Self::LATIN_SUBJECTS.choose(rng).unwrap()
+                ),
+                format!(
+                    "Illustrissimo Domino {} hanc tabulam dedicat",
+                    Self::LATIN_PATRONS.choose(rng).unwrap()
+                ),
+                format!("Anno Domini {}", roman(year)),
+            ),
+            Style::Plain => (
+                format!("A New Map of {}", Self::PLAIN_SUBJECTS.choose(rng).unwrap()),
+                format!(
+                    "Humbly dedicated to {}",
+                    Self::PLAIN_PATRONS.choose(rng).unwrap()
+                ),
+                format!("Surveyed in the year {year}"),
+            ),
+        };
+        self.title = title;
+        self.dedication = dedication;
+        self.date = date;
+        self.mesh = None;
+    }
+
+    /// Returns the title line.
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// Replaces the title line.
+    pub fn set_title(&mut self, title: String) {
+        self.title = title;
+        self.mesh = None;
+    }
+
+    /// Returns the dedication line.
+    pub fn dedication(&self) -> &str {
+        &self.dedication
+    }
+
+    /// Replaces the dedication line.
+    pub fn set_dedication(&mut self, dedication: String) {
+        self.dedication = dedication;
+        self.mesh = None;
+    }
+
+    /// Returns the date line.
+    pub fn date(&self) -> &str {
+        &self.date
+    }
+
+    /// Replaces the date line.
+    pub fn set_date(&mut self, date: String) {
+        self.date = date;
+        self.mesh = None;
+    }
+
+    /// Returns the line being typed into, if any.
+    pub fn editing(&self) -> Option<Line> {
+        self.editing
+    }
+
+    /// Starts typing into `line`, or stops typing with `None`.
+    pub fn set_editing(&mut self, editing: Option<Line>) {
+        self.editing = editing;
+        self.mesh = None;
+    }
+
+    /// Appends a character to the line being typed into.
+    pub fn push(&mut self, character: char) {
+        if let Some(line) = self.editing {
+            self.line_mut(line).push(character);
+            self.mesh = None;
+        }
+    }
+
+    /// Removes the last character of the line being typed into.
+    pub fn pop(&mut self) {
+        if let Some(line) = self.editing {
+            self.line_mut(line).pop();
+            self.mesh = None;
+        }
+    }
+
+    fn line_mut(&mut self, line: Line) -> &mut String {
+        match line {
+            Line::Title => &mut self.title,
+            Line::Dedication => &mut self.dedication,
+            Line::Date => &mut self.date,
+        }
+    }
+
+    /// Change the color of the text and frame.
+    /// params:
+    /// color: [r, g, b, a]
+    pub fn color_mut(&mut self, color: [f32; 4]) {
+        self.params.color_mut(color);
+        self.mesh = None;
+    }
+
+    fn lines(&self) -> [Text; 3] {
+        let params = &self.params;
+        let line = |kind: Line, s: &str, scale: f32| {
+            let caret = match self.editing == Some(kind) {
+                true => "_",
+                false => "",
+            };
+            Text::new(
+                TextFragment::from(format!("{s}{caret}"))
+                    .font(params.font())
+                    .scale(params.height().y * scale)
+                    .color(*params.color()),
+            )
+        };
+        [
+            line(Line::Title, &self.title, Self::TITLE_SCALE),
+            line(Line::Dedication, &self.dedication, 1.0),
+            line(Line::Date, &self.date, 1.0),
+        ]
+    }
+
+    /// Measures every line and the frame around them: (line sizes, frame size)
+    fn measure(&self, ctx: &Context) -> GameResult<(Vec<Vec2>, Vec2)> {
+        let sizes = self
+            .lines()
+            .iter()
+            .map(|line| line.measure(ctx))
+            .collect::<GameResult<Vec<Vec2>>>()?;
+        let inner = Vec2::new(
+            sizes.iter().map(|size| size.x).fold(0.0, f32::max),
+            sizes.iter().map(|size| size.y).sum(),
+        );
+        Ok((sizes, inner + Vec2::splat(Self::PADDING * 2.0)))
+    }
+}
+
+impl Layer for Cartouche {
+    fn name(&self) -> &str {
+        "cartouche"
+    }
+
+    fn kind(&self) -> LayerKind {
+        LayerKind::Frame
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
+        let lines = self.lines();
+        let (sizes, outer) = self.measure(ctx)?;
+        let frame = self.focus_bounds(ctx, canvas)?;
+        let origin = Vec2::new(frame.x, frame.y);
+
+        if self.mesh.is_none() {
+            let color = graphics::Color::from(*self.params.color());
+            let stroke = self.params.stroke_width();
+            let inset = stroke * 3.0;
+            let mut builder = graphics::MeshBuilder::new();
+            builder
+                .rectangle(
+                    graphics::DrawMode::stroke(stroke * 2.0),
+                    graphics::Rect::new(0.0, 0.0, outer.x, outer.y),
+                    color,
+                )?
+                .rectangle(
+                    graphics::DrawMode::stroke(stroke),
+                    graphics::Rect::new(inset, inset, outer.x - inset * 2.0, outer.y - inset * 2.0),
+                    color,
+                )?;
+            self.mesh = Some(Mesh::from_data(ctx, builder.build()));
+        }
+        if let Some(mesh) = &self.mesh {
+            canvas.draw(mesh, DrawParam::default().dest(origin));
+        }
+
+        // center every line inside the frame
+        let mut y = origin.y + Self::PADDING;
+        for (line, size) in lines.iter().zip(sizes) {
+            let x = origin.x + (outer.x - size.x) / 2.0;
+            canvas.draw(line, DrawParam::default().dest(Vec2::new(x, y)));
+            y += size.y;
+        }
+        Ok(())
+    }
+
+    fn focus_bounds(&self, ctx: &Context, canvas: &Canvas) -> GameResult<graphics::Rect> {
+        let (_, outer) = self.measure(ctx)?;
+        let bounds = canvas_bounds(ctx, canvas);
+        Ok(graphics::Rect::new(
+            bounds.right() - outer.x - self.margin,
+            bounds.top() + self.margin,
+            outer.x,
+            outer.y,
+        ))
+    }
+}
+
+/// Formats a positive number as roman numerals.
+fn roman(mut n: u32) -> String {
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut out = String::new();
+    for (value, numeral) in NUMERALS {
+        while n >= value {
+            out.push_str(numeral);
+            n -= value;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roman_numerals() {
+        assert_eq!(roman(1), "I");
+        assert_eq!(roman(4), "IV");
+        assert_eq!(roman(9), "IX");
+        assert_eq!(roman(14), "XIV");
+        assert_eq!(roman(40), "XL");
+        assert_eq!(roman(90), "XC");
+        assert_eq!(roman(400), "CD");
+        assert_eq!(roman(1450), "MCDL");
+        assert_eq!(roman(1799), "MDCCXCIX");
+        assert_eq!(roman(3999), "MMMCMXCIX");
+    }
+
+    #[test]
+    fn roman_zero_is_empty() {
+        assert_eq!(roman(0), "");
+    }
+}
@@ -0,0 +1,170 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use ggez::{graphics::Image, Context, GameError, GameResult};
+
+use super::layers::LayerKind;
+use crate::util::time::DateTime;
+
+/// Largest export side in pixels, the usual GPU texture limit.
+pub const MAX_SIZE: u32 = 16384;
+
+/// Settings for raster map exports.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ExportOptions {
+    /// Multiplier applied to the window size by window-sized exports.
+    pub scale: f32,
+    /// Border around the map in pixels, filled with the background.
+    pub margin: u32,
+    /// Leaves the background transparent instead of the palette color and parchment.
+    pub transparent: bool,
+    /// Includes UI layers such as the log.
+    pub include_ui: bool,
+}
+
+impl ExportOptions {
+    /// Returns true if layers of `kind` are part of the export.
+    pub fn includes(&self, kind: LayerKind) -> bool {
+        match kind {
+            LayerKind::Ui => self.include_ui,
+            LayerKind::Background => !self.transparent,
+            _ => true,
+        }
+    }
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            scale: 2.0,
+            margin: 0,
+            transparent: false,
+            include_ui: false,
+        }
+    }
+}
+
+/// Returns a timestamped path next to the executable: `cartographer-<name>-<timestamp>.<ext>`.
+pub fn output_path(name: &str, ext: &str) -> GameResult<PathBuf> {
+    let dir = env::current_exe()?
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+    Ok(dir.join(format!(
+        "cartographer-{name}-{}.{ext}",
+        DateTime::now().compact()
+    )))
+}
+
+/// Writes an RGBA8 image to `path` as a PNG.
+///
+/// `path` is a regular file system path, not one relative to the ggez resource directories.
+pub fn save_png(ctx: &Context, image: &Image, path: impl AsRef<Path>) -> GameResult {
+    let pixels = image.to_pixels(ctx)?;
+    image::save_buffer(
+        path,
+        &pixels,
+        image.width(),
+        image.height(),
+        image::ColorType::Rgba8,
+    )
+    .map_err(|e| GameError::CustomError(format!("could not write png: {e}")))
+}
+
+/// Copies the file at `path` into `dir` and returns the path of the copy.
+///
+/// Existing files are never overwritten, a numbered suffix is added instead: `name-1.png`.
+/// The copy is written under a temporary name and renamed once complete,
+/// so sync clients watching `dir` never pick up a partial file.
+pub fn mirror(path: impl AsRef<Path>, dir: impl AsRef<Path>) -> GameResult<PathBuf> {
+    let (path, dir) = (path.as_ref(), dir.as_ref());
+    let name = path
+        .file_name()
+        .ok_or_else(|| GameError::CustomError(format!("not a file: {}", path.display())))?;
+    fs::create_dir_all(dir)?;
+
+    let stem = Path::new(name)
+        .file_stem()
+        .unwrap_or(name)
+        .to_string_lossy();
+    let ext = path
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+    let target = (0..)
+        .map(|i| match i {
+            0 => dir.join(name),
+            i => dir.join(format!("{stem}-{i}{ext}")),
+        })
+        .find(|target| !target.exists())
+        .unwrap();
+
+    let partial = dir.join(format!(".{}.partial", name.to_string_lossy()));
+    fs::copy(path, &partial)?;
+    fs::rename(&partial, &target)?;
+    Ok(target)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns a fresh directory in the temp directory unique to the calling test,
+    /// with a source file `map.png` inside it.
+    fn temp_dir(name: &str) -> (PathBuf, PathBuf) {
+        let dir =
+            env::temp_dir().join(format!("cartographer-export-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let source = dir.join("map.png");
+        fs::write(&source, b"map").unwrap();
+        (dir, source)
+    }
+
+    #[test]
+    fn mirror_creates_the_directory() {
+        let (dir, source) = temp_dir("create");
+        let sync = dir.join("sync").join("maps");
+        let copy = mirror(&source, &sync).unwrap();
+        assert_eq!(copy, sync.join("map.png"));
+        assert_eq!(fs::read(&copy).unwrap(), b"map");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn mirror_numbers_existing_targets() {
+        let (dir, source) = temp_dir("suffix");
+        let sync = dir.join("sync");
+        fs::create_dir_all(&sync).unwrap();
+        fs::write(sync.join("map.png"), b"older").unwrap();
+
+        assert_eq!(mirror(&source, &sync).unwrap(), sync.join("map-1.png"));
+        assert_eq!(mirror(&source, &sync).unwrap(), sync.join("map-2.png"));
+        // the existing file is left alone
+        assert_eq!(fs::read(sync.join("map.png")).unwrap(), b"older");
+        assert_eq!(fs::read(sync.join("map-2.png")).unwrap(), b"map");
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn mirror_renames_the_partial_copy() {
+        let (dir, source) = temp_dir("partial");
+        let sync = dir.join("sync");
+        fs::create_dir_all(&sync).unwrap();
+        // left behind by an interrupted copy
+        let partial = sync.join(".map.png.partial");
+        fs::write(&partial, b"interrupted").unwrap();
+
+        let copy = mirror(&source, &sync).unwrap();
+        assert_eq!(fs::read(&copy).unwrap(), b"map");
+        assert!(!partial.exists());
+        let names: Vec<_> = fs::read_dir(&sync)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["map.png"]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,239 @@
+use ggez::{
+    glam::Vec2,
+    graphics::{self, Canvas, DrawParam, Mesh, Rect},
+    Context, GameResult,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    layers::{canvas_bounds, Layer, LayerKind},
+    Result,
+};
+
+/// Shape of the cells drawn by a [`Grid`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GridShape {
+    Square,
+    /// Flat-topped hexes, `cell_size` is the distance between opposite sides.
+    Hex,
+}
+
+/// Toggleable grid overlay drawn over the map in world space.
+#[derive(Debug)]
+pub struct Grid {
+    shape: GridShape,
+    cell_size: f32,
+    opacity: f32,
+    color: [f32; 4],
+    stroke_width: f32,
+    visible: bool,
+    /// Whether grids denser than [`Grid::MAX_CELLS`] are skipped.
+    capped: bool,
+    /// Cached mesh and the range of cells it covers: (left, top, right, bottom).
+    mesh: Option<(Mesh, (i32, i32, i32, i32))>,
+}
+
+impl Grid {
+    const MIN_CELL: f32 = 8.0;
+    const MAX_CELL: f32 = 512.0;
+    /// Cells beyond this count are not drawn while capped, the grid would only be noise.
+    const MAX_CELLS: i64 = 20_000;
+
+    /// Creates a new [`Grid`] instance.
+    /// Parameters:
+    /// - `shape`: square or hex cells
+    /// - `cell_size`: size of a cell in world units
+    /// - `opacity`: alpha applied on top of `color`, between 0 and 1
+    /// - `color`: [r, g, b, a]
+    /// - `stroke_width`: width of the grid lines
+    pub fn new(
+        shape: GridShape,
+        cell_size: f32,
+        opacity: f32,
+        color: [f32; 4],
+        stroke_width: f32,
+    ) -> Grid {
+        Self {
+            shape,
+            cell_size: cell_size.clamp(Self::MIN_CELL, Self::MAX_CELL),
+            opacity: opacity.clamp(0.0, 1.0),
+            color,
+            stroke_width,
+            visible: false,
+            capped: true,
+            mesh: None,
+        }
+    }
+
+    /// Returns the cell shape.
+    pub fn shape(&self) -> GridShape {
+        self.shape
+    }
+
+    /// Changes the cell shape.
+    pub fn set_shape(&mut self, shape: GridShape) {
+        self.shape = shape;
+        self.mesh = None;
+    }
+
+    /// Returns the cell size in world units.
+    pub fn cell_size(&self) -> f32 {
+        self.cell_size
+    }
+
+    /// Changes the cell size, clamped to a sensible range.
+    pub fn set_cell_size(&mut self, cell_size: f32) {
+        self.cell_size = cell_size.clamp(Self::MIN_CELL, Self::MAX_CELL);
+        self.mesh = None;
+    }
+
+    /// Returns the opacity of the grid lines.
+    pub fn opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    /// Changes the opacity of the grid lines, clamped between 0 and 1.
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self.mesh = None;
+    }
+
+    /// Change the color of the grid.
+    /// params:
+    /// color: [r, g, b, a]
+    pub fn color_mut(&mut self, color: [f32; 4]) {
+        self.color = color;
+        self.mesh = None;
+    }
+
+    /// Skips or draws grids denser than [`Grid::MAX_CELLS`].
+    ///
+    /// On by default, exports with a fixed size per cell turn it off so every cell is drawn.
+    pub fn set_capped(&mut self, capped: bool) {
+        self.capped = capped;
+    }
+
+    /// Returns the range of cells touching `view`: (left, top, right, bottom).
+    fn cells(&self, view: Rect) -> (i32, i32, i32, i32) {
+        let (step_x, step_y) = match self.shape {
+            GridShape::Square => (self.cell_size, self.cell_size),
+            GridShape::Hex => (self.hex_radius() * 1.5, self.cell_size),
+        };
+        (
+            (view.left() / step_x).floor() as i32 - 1,
+            (view.top() / step_y).floor() as i32 - 1,
+            (view.right() / step_x).ceil() as i32 + 1,
+            (view.bottom() / step_y).ceil() as i32 + 1,
+        )
+    }
+
+    /// Distance from the center of a hex to its corners.
+    fn hex_radius(&self) -> f32 {
+        self.cell_size / 3f32.sqrt()
+    }
+
+    fn build(&self, ctx: &Context, cells: (i32, i32, i32, i32)) -> Result<Mesh> {
+        let (left, top, right, bottom) = cells;
+        let mut color = graphics::Color::from(self.color);
+        color.a *= self.opacity;
+
+        let mut builder = graphics::MeshBuilder::new();
+        match self.shape {
+            GridShape::Square => {
+                let size = self.cell_size;
+                for x in left..=right {
+                    let x = x as f32 * size;
+                    builder.line(
+                        &[
+                            Vec2::new(x, top as f32 * size),
+                            Vec2::new(x, bottom as f32 * size),
+                        ],
+                        self.stroke_width,
+                        color,
+                    )?;
+                }
+                for y in top..=bottom {
+                    let y = y as f32 * size;
+                    builder.line(
+                        &[
+                            Vec2::new(left as f32 * size, y),
+                            Vec2::new(right as f32 * size, y),
+                        ],
+                        self.stroke_width,
+                        color,
+                    )?;
+                }
+            }
+            GridShape::Hex => {
+                let radius = self.hex_radius();
+                let corners: Vec<Vec2> = (0..6)
+                    .map(|i| {
+                        let angle = (60.0 * i as f32).to_radians();
+                        Vec2::new(angle.cos(), angle.sin()) * radius
+                    })
+                    .collect();
+                for col in left..=right {
+                    // odd columns are shifted down by half a cell
+                    let shift = match col.rem_euclid(2) {
+                        1 => self.cell_size / 2.0,
+                        _ => 0.0,
+                    };
+                    for row in top..=bottom {
+                        let center = Vec2::new(
+                            col as f32 * radius * 1.5,
+                            row as f32 * self.cell_size + shift,
+                        );
+                        let points: Vec<Vec2> = corners.iter().map(|c| center + *c).collect();
+                        builder.polygon(
+                            graphics::DrawMode::stroke(self.stroke_width),
+                            &points,
+                            color,
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(Mesh::from_data(ctx, builder.build()))
+    }
+}
+
+impl Layer for Grid {
+    fn name(&self) -> &str {
+        "grid"
+    }
+
+    fn kind(&self) -> LayerKind {
+        LayerKind::Grid
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Draws the cells covering the visible area.
+    ///
+    /// The mesh is only rebuilt when the visible cell range or the grid settings change.
+    /// Nothing is drawn if the range is too dense and the grid is capped.
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
+        let view = canvas_bounds(ctx, canvas);
+        let cells = self.cells(view);
+        let (left, top, right, bottom) = cells;
+        if self.capped && (right - left) as i64 * (bottom - top) as i64 > Self::MAX_CELLS {
+            return Ok(());
+        }
+
+        let stale = !matches!(&self.mesh, Some((_, cached)) if *cached == cells);
+        if stale {
+            self.mesh = Some((self.build(ctx, cells)?, cells));
+        }
+        if let Some((mesh, _)) = &self.mesh {
+            canvas.draw(mesh, DrawParam::default());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,72 @@
+use ggez::{
+    graphics::{Canvas, Rect},
+    Context, GameResult,
+};
+
+/// Draw order of the scene, from bottom to top.
+///
+/// Every layer except [`LayerKind::Background`], [`LayerKind::Lighting`],
+/// [`LayerKind::Frame`] and [`LayerKind::Ui`] is drawn in world space through the camera.
+#[allow(unused)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LayerKind {
+    Background,
+    Terrain,
+    Water,
+    Features,
+    Grid,
+    Labels,
+    Lighting,
+    /// Map furniture such as the cartouche, part of exports unlike [`LayerKind::Ui`].
+    Frame,
+    Debug,
+    Ui,
+}
+
+impl LayerKind {
+    /// Returns true if the layer is drawn through the camera.
+    pub fn is_world_space(&self) -> bool {
+        !matches!(
+            self,
+            LayerKind::Background | LayerKind::Lighting | LayerKind::Frame | LayerKind::Ui
+        )
+    }
+}
+
+/// A drawable part of the scene with its own visibility.
+pub trait Layer {
+    /// Name of the layer, used in messages and toggles.
+    fn name(&self) -> &str;
+
+    /// Position of the layer in the draw order.
+    fn kind(&self) -> LayerKind;
+
+    /// Returns whether the layer is drawn.
+    fn visible(&self) -> bool;
+
+    /// Shows or hides the layer.
+    fn set_visible(&mut self, visible: bool);
+
+    /// Draws the layer onto the canvas.
+    ///
+    /// The canvas screen coordinates are already set up for the layer's [`LayerKind`].
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult;
+
+    /// Returns the screen area outlined while the layer is selected.
+    ///
+    /// Defaults to the whole canvas, for layers spread over the map.
+    fn focus_bounds(&self, ctx: &Context, canvas: &Canvas) -> GameResult<Rect> {
+        Ok(canvas_bounds(ctx, canvas))
+    }
+}
+
+/// Returns the area covered by the canvas.
+///
+/// Layers should lay themselves out with this rather than the window size,
+/// the scene may be rendered into an offscreen image of a different size.
+pub fn canvas_bounds(ctx: &Context, canvas: &Canvas) -> Rect {
+    canvas.screen_coordinates().unwrap_or_else(|| {
+        let (width, height) = ctx.gfx.drawable_size();
+        Rect::new(0.0, 0.0, width, height)
+    })
+}
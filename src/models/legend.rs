@@ -0,0 +1,232 @@
+use ggez::{
+    glam::Vec2,
+    graphics::{self, Canvas, DrawParam, Mesh, Rect, Text, TextFragment},
+    Context, GameResult,
+};
+
+use super::{
+    layers::{canvas_bounds, Layer, LayerKind},
+    logger::TextParams,
+};
+
+/// Symbol drawn next to a legend label.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Symbol {
+    /// Filled square of a map color, [r, g, b, a]
+    Swatch([f32; 4]),
+    /// Scale bar of a length in world units.
+    Scale(f32),
+}
+
+/// Key panel explaining the map, drawn in the bottom-right corner.
+///
+/// The entries are set by the owner from the layers present,
+/// the panel only lays them out.
+#[derive(Debug)]
+pub struct Legend {
+    entries: Vec<(Symbol, String)>,
+    params: TextParams,
+    margin: f32,
+    /// Screen pixels per world unit, used for scale bars.
+    scale: f32,
+    visible: bool,
+}
+
+impl Legend {
+    const PADDING: f32 = 12.0;
+    const SPACING: f32 = 8.0;
+    const TITLE: &'static str = "Legend";
+
+    /// Creates a new, hidden [`Legend`] without entries.
+    /// Parameters:
+    /// - `params`: text appearance, the stroke width is used for the frame and symbols
+    /// - `margin`: distance from the bottom-right corner of the screen
+    pub fn new(params: TextParams, margin: f32) -> Self {
+        Self {
+            entries: vec![],
+            params,
+            margin,
+            scale: 1.0,
+            visible: false,
+        }
+    }
+
+    /// Replaces the entries, drawn top to bottom.
+    pub fn set_entries(&mut self, entries: Vec<(Symbol, String)>) {
+        self.entries = entries;
+    }
+
+    /// Sets the screen pixels per world unit, scale bars follow the map zoom with it.
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale;
+    }
+
+    /// Change the color of the text and frame.
+    /// params:
+    /// color: [r, g, b, a]
+    pub fn color_mut(&mut self, color: [f32; 4]) {
+        self.params.color_mut(color);
+    }
+
+    /// Sets the height of the text in pixels.
+    pub fn set_text_height(&mut self, height: f32) {
+        self.params.set_height(height);
+    }
+
+    /// Returns the size of the panel including its frame: (width, height)
+    pub fn size(&self, ctx: &Context) -> GameResult<Vec2> {
+        let (title, rows) = self.rows(ctx)?;
+        let inner = Vec2::new(
+            rows.iter().map(|(width, _)| *width).fold(title.x, f32::max),
+            title.y
+                + rows
+                    .iter()
+                    .map(|(_, height)| height + Self::SPACING)
+                    .sum::<f32>(),
+        );
+        Ok(inner + Vec2::splat(Self::PADDING * 2.0))
+    }
+
+    /// Draws the panel with its top-left corner at `origin`, independent of the canvas bounds.
+    pub fn draw_at(&self, ctx: &mut Context, canvas: &mut Canvas, origin: Vec2) -> GameResult {
+        let color = graphics::Color::from(*self.params.color());
+        let stroke = self.params.stroke_width();
+        let size = self.size(ctx)?;
+        let (title, rows) = self.rows(ctx)?;
+        let square = self.symbol_size().y;
+
+        let mut builder = graphics::MeshBuilder::new();
+        builder.rectangle(
+            graphics::DrawMode::stroke(stroke * 2.0),
+            Rect::new(0.0, 0.0, size.x, size.y),
+            color,
+        )?;
+        let mut y = Self::PADDING + title.y + Self::SPACING;
+        for ((symbol, _), (_, height)) in self.entries.iter().zip(&rows) {
+            let top = y + (height - square) / 2.0;
+            match symbol {
+                Symbol::Swatch(swatch) => {
+                    let area = Rect::new(Self::PADDING, top, square, square);
+                    builder
+                        .rectangle(
+                            graphics::DrawMode::fill(),
+                            area,
+                            graphics::Color::from(*swatch),
+                        )?
+                        .rectangle(graphics::DrawMode::stroke(stroke), area, color)?;
+                }
+                Symbol::Scale(length) => {
+                    // a bar with end ticks, centered on the row
+                    let (left, right) = (Self::PADDING, Self::PADDING + length * self.scale);
+                    let middle = top + square / 2.0;
+                    builder
+                        .line(
+                            &[Vec2::new(left, middle), Vec2::new(right, middle)],
+                            stroke,
+                            color,
+                        )?
+                        .line(
+                            &[Vec2::new(left, top), Vec2::new(left, top + square)],
+                            stroke,
+                            color,
+                        )?
+                        .line(
+                            &[Vec2::new(right, top), Vec2::new(right, top + square)],
+                            stroke,
+                            color,
+                        )?;
+                }
+            }
+            y += height + Self::SPACING;
+        }
+        let mesh = Mesh::from_data(ctx, builder.build());
+        canvas.draw(&mesh, DrawParam::default().dest(origin));
+
+        canvas.draw(
+            &self.line(Self::TITLE),
+            DrawParam::default().dest(origin + Vec2::splat(Self::PADDING)),
+        );
+        let mut y = origin.y + Self::PADDING + title.y + Self::SPACING;
+        for ((symbol, label), (_, height)) in self.entries.iter().zip(&rows) {
+            let x = origin.x + Self::PADDING + self.symbol_width(symbol) + Self::SPACING;
+            canvas.draw(
+                &self.line(label),
+                DrawParam::default().dest(Vec2::new(x, y)),
+            );
+            y += height + Self::SPACING;
+        }
+        Ok(())
+    }
+
+    fn line(&self, s: &str) -> Text {
+        Text::new(
+            TextFragment::from(s)
+                .font(self.params.font())
+                .scale(self.params.height().y)
+                .color(*self.params.color()),
+        )
+    }
+
+    /// Size of the square symbols, matching the text height.
+    fn symbol_size(&self) -> Vec2 {
+        Vec2::splat(self.params.height().y)
+    }
+
+    fn symbol_width(&self, symbol: &Symbol) -> f32 {
+        match symbol {
+            Symbol::Swatch(_) => self.symbol_size().x,
+            Symbol::Scale(length) => length * self.scale,
+        }
+    }
+
+    /// Measures the title and every entry row: (title size, [(row width, row height)])
+    fn rows(&self, ctx: &Context) -> GameResult<(Vec2, Vec<(f32, f32)>)> {
+        let title = self.line(Self::TITLE).measure(ctx)?;
+        let rows = self
+            .entries
+            .iter()
+            .map(|(symbol, label)| {
+                let text = self.line(label).measure(ctx)?;
+                Ok((
+                    self.symbol_width(symbol) + Self::SPACING + text.x,
+                    text.y.max(self.symbol_size().y),
+                ))
+            })
+            .collect::<GameResult<Vec<(f32, f32)>>>()?;
+        Ok((title, rows))
+    }
+}
+
+impl Layer for Legend {
+    fn name(&self) -> &str {
+        "legend"
+    }
+
+    fn kind(&self) -> LayerKind {
+        LayerKind::Frame
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
+        let area = self.focus_bounds(ctx, canvas)?;
+        self.draw_at(ctx, canvas, Vec2::new(area.x, area.y))
+    }
+
+    fn focus_bounds(&self, ctx: &Context, canvas: &Canvas) -> GameResult<Rect> {
+        let size = self.size(ctx)?;
+        let bounds = canvas_bounds(ctx, canvas);
+        Ok(Rect::new(
+            bounds.right() - size.x - self.margin,
+            bounds.bottom() - size.y - self.margin,
+            size.x,
+            size.y,
+        ))
+    }
+}
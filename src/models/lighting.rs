@@ -0,0 +1,97 @@
+use std::f32::consts::TAU;
+
+use ggez::{
+    graphics::{self, Canvas, DrawMode, DrawParam, Mesh},
+    Context, GameResult,
+};
+
+use super::layers::{canvas_bounds, Layer, LayerKind};
+
+/// Darkens the map according to a time of day.
+#[derive(Debug)]
+pub struct Lighting {
+    /// Hours since midnight, in `0.0..24.0`.
+    time: f32,
+    /// In-game hours advanced per real second, 0 pauses the clock.
+    speed: f32,
+    night: [f32; 4],
+    visible: bool,
+}
+
+impl Lighting {
+    const HOURS: f32 = 24.0;
+    /// Alpha of the overlay at midnight.
+    const MAX_DARKNESS: f32 = 0.75;
+
+    /// Creates a new [`Lighting`] instance.
+    /// Parameters:
+    /// - `time`: hours since midnight
+    /// - `speed`: in-game hours advanced per second while animating
+    /// - `night`: [r, g, b, a] color of the overlay, the alpha is driven by the time
+    pub fn new(time: f32, speed: f32, night: [f32; 4]) -> Lighting {
+        Self {
+            time: time.rem_euclid(Self::HOURS),
+            speed,
+            night,
+            visible: false,
+        }
+    }
+
+    /// Returns the hours since midnight.
+    pub fn time(&self) -> f32 {
+        self.time
+    }
+
+    /// Sets the hours since midnight, wrapping around the day.
+    pub fn set_time(&mut self, time: f32) {
+        self.time = time.rem_euclid(Self::HOURS);
+    }
+
+    /// Returns the in-game hours advanced per second.
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Sets the in-game hours advanced per second, 0 pauses the clock.
+    pub fn set_speed(&mut self, speed: f32) {
+        self.speed = speed;
+    }
+
+    /// Returns the overlay alpha for the current time, 0 at noon.
+    pub fn darkness(&self) -> f32 {
+        let angle = self.time / Self::HOURS * TAU;
+        (angle.cos() + 1.0) / 2.0 * Self::MAX_DARKNESS
+    }
+
+    /// Advances the clock by `dt` real seconds.
+    pub fn update(&mut self, dt: f32) {
+        self.set_time(self.time + self.speed * dt);
+    }
+}
+
+impl Layer for Lighting {
+    fn name(&self) -> &str {
+        "lighting"
+    }
+
+    fn kind(&self) -> LayerKind {
+        LayerKind::Lighting
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
+        let mut color = graphics::Color::from(self.night);
+        color.a = self.darkness();
+        let overlay =
+            Mesh::new_rectangle(ctx, DrawMode::fill(), canvas_bounds(ctx, canvas), color)?;
+        canvas.draw(&overlay, DrawParam::default());
+        Ok(())
+    }
+}
@@ -1,22 +1,333 @@
+use std::{
+    collections::VecDeque,
+    fs::{File, OpenOptions},
+    io::{LineWriter, Write},
+    ops::RangeBounds,
+    path::Path,
+    str::FromStr,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+    time::SystemTime,
+};
+
 use ggez::{
     context::Has,
     glam::Vec2,
-    graphics::{self, Drawable, GraphicsContext, Mesh, PxScale, Text, TextFragment},
+    graphics::{self, Canvas, DrawParam, GraphicsContext, Mesh, PxScale, Text, TextFragment},
+    Context, GameError, GameResult,
 };
 
-use super::Result;
+use super::{
+    layers::{canvas_bounds, Layer, LayerKind},
+    Result,
+};
+use crate::util::time::DateTime;
+
+/// Severity of a log entry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl Level {
+    /// Returns the glyph shown before entries of this level.
+    pub fn glyph(&self) -> &'static str {
+        match self {
+            Level::Debug => "·",
+            Level::Info => "›",
+            Level::Warn => "!",
+            Level::Error => "×",
+        }
+    }
+
+    /// Derives the color of this level from the base text color: [r, g, b, a]
+    pub fn color(&self, base: [f32; 4]) -> [f32; 4] {
+        match self {
+            Level::Debug => [base[0], base[1], base[2], base[3] * 0.6],
+            Level::Info => base,
+            Level::Warn => mix(base, [1.0, 0.7, 0.1]),
+            Level::Error => mix(base, [0.95, 0.25, 0.2]),
+        }
+    }
+}
+
+/// Moves `base` 60% of the way towards `tint`, keeping its alpha.
+fn mix(base: [f32; 4], tint: [f32; 3]) -> [f32; 4] {
+    let [r, g, b, a] = base;
+    let t = 0.6;
+    [
+        r + (tint[0] - r) * t,
+        g + (tint[1] - g) * t,
+        b + (tint[2] - b) * t,
+        a,
+    ]
+}
+
+/// Color of a [`Span`], derived from the base text color so it follows palette changes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Tone {
+    /// The color of the entry's [`Level`].
+    Plain,
+    /// Stands out from the surrounding text, e.g. seeds and paths.
+    Accent,
+    /// Recedes behind the surrounding text, e.g. timestamps and labels.
+    Muted,
+    /// A fixed color: [r, g, b, a]
+    Color([f32; 4]),
+}
+
+impl Tone {
+    /// Returns the color of the tone for text of `level` in the `base` color.
+    pub fn color(&self, base: [f32; 4], level: Level) -> [f32; 4] {
+        match self {
+            Tone::Plain => level.color(base),
+            Tone::Accent => mix(base, [0.3, 0.75, 1.0]),
+            Tone::Muted => Level::Debug.color(base),
+            Tone::Color(color) => *color,
+        }
+    }
+}
+
+/// Part of a rich log entry, see [`Log::push_rich`].
+#[derive(Debug, Clone)]
+pub struct Span {
+    text: String,
+    tone: Tone,
+}
+
+impl Span {
+    /// Creates a new [`Span`] drawn in `tone`.
+    pub fn new(text: impl Into<String>, tone: Tone) -> Span {
+        Self {
+            text: text.into(),
+            tone,
+        }
+    }
+
+    /// Creates a [`Span`] in the entry's own color.
+    pub fn plain(text: impl Into<String>) -> Span {
+        Self::new(text, Tone::Plain)
+    }
+
+    /// Creates a [`Span`] in the accent color.
+    pub fn accent(text: impl Into<String>) -> Span {
+        Self::new(text, Tone::Accent)
+    }
+
+    /// Creates a [`Span`] in the muted color.
+    pub fn muted(text: impl Into<String>) -> Span {
+        Self::new(text, Tone::Muted)
+    }
+}
+
+/// How entry times are shown in front of log messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Timestamps {
+    Off,
+    /// Wall clock time in UTC: `[HH:MM:SS]`
+    Clock,
+    /// Time since the log was created: `[+MM:SS]`
+    Relative,
+}
+
+impl FromStr for Timestamps {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "off" => Ok(Timestamps::Off),
+            "clock" => Ok(Timestamps::Clock),
+            "relative" => Ok(Timestamps::Relative),
+            _ => Err(format!("expected off, clock or relative, found {s}")),
+        }
+    }
+}
+
+/// Subsystem an entry came from, used to filter the log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Category {
+    Generation,
+    Input,
+    Export,
+    System,
+}
+
+impl Category {
+    /// All categories, in the order they are listed in the log header.
+    pub const ALL: [Category; 4] = [
+        Category::Generation,
+        Category::Input,
+        Category::Export,
+        Category::System,
+    ];
+
+    /// Returns the short name shown in the log header.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Category::Generation => "gen",
+            Category::Input => "input",
+            Category::Export => "export",
+            Category::System => "system",
+        }
+    }
+}
+
+/// Part of the log box grabbed with the mouse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Handle {
+    /// The border, dragging moves the box.
+    Border,
+    /// The top-right corner, dragging resizes the box.
+    Corner,
+}
+
+/// A single message in the [`Log`].
+#[derive(Debug, Clone)]
+struct Entry {
+    level: Level,
+    category: Category,
+    /// Full text of the entry, the spans concatenated.
+    message: String,
+    /// Tone of each character of `message`, empty for plain entries.
+    tones: Vec<Tone>,
+    time: SystemTime,
+    /// Number of lines the entry wraps to in the box, `None` until measured.
+    height: Option<usize>,
+}
+
+impl Entry {
+    /// Splits the wrapped `lines` of the message into runs of the same tone.
+    ///
+    /// The wrapped lines drop the space at each word break, so the position in
+    /// `message` skips it when the next line does not start with it.
+    fn runs(&self, lines: &[String]) -> Vec<Vec<(String, Tone)>> {
+        if self.tones.is_empty() {
+            return lines
+                .iter()
+                .map(|line| vec![(line.clone(), Tone::Plain)])
+                .collect();
+        }
+
+        let chars: Vec<char> = self.message.chars().collect();
+        let mut cursor = 0;
+        lines
+            .iter()
+            .map(|line| {
+                if chars.get(cursor) == Some(&' ') && !line.starts_with(' ') {
+                    cursor += 1;
+                }
+                let mut runs: Vec<(String, Tone)> = vec![];
+                for c in line.chars() {
+                    let tone = self.tones.get(cursor).copied().unwrap_or(Tone::Plain);
+                    cursor += 1;
+                    match runs.last_mut() {
+                        Some((text, last)) if *last == tone => text.push(c),
+                        _ => runs.push((c.to_string(), tone)),
+                    }
+                }
+                runs
+            })
+            .collect()
+    }
+}
+
+/// Record forwarded from the `log` facade: (level, category, message).
+type Forwarded = (Level, Category, String);
+
+/// Backend for the `log` facade that forwards records into a [`Log`].
+///
+/// The facade may be called from any thread, so records are queued on a channel
+/// and moved into the log by [`Log::drain`].
+struct Bridge {
+    sender: Mutex<Sender<Forwarded>>,
+    level: log::LevelFilter,
+}
+
+impl Bridge {
+    const CORE: &'static str = "cartography_core";
+}
+
+impl log::Log for Bridge {
+    /// Records from this crate and `cartography_core` are forwarded up to the configured level,
+    /// other crates (ggez, wgpu) only from warnings up.
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        let target = metadata.target();
+        match target.starts_with(env!("CARGO_CRATE_NAME")) || target.starts_with(Self::CORE) {
+            true => metadata.level() <= self.level,
+            false => metadata.level() <= log::Level::Warn,
+        }
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let level = match record.level() {
+            log::Level::Error => Level::Error,
+            log::Level::Warn => Level::Warn,
+            log::Level::Info => Level::Info,
+            log::Level::Debug | log::Level::Trace => Level::Debug,
+        };
+        let category = match record.target().starts_with(Self::CORE) {
+            true => Category::Generation,
+            false => Category::System,
+        };
+        if let Ok(sender) = self.sender.lock() {
+            // the receiver is gone once the log is dropped, nothing left to show the record
+            let _ = sender.send((level, category, record.args().to_string()));
+        }
+    }
+
+    fn flush(&self) {}
+}
 
 /// Logging system for displaying text on screen.
 #[derive(Debug)]
 pub struct Log {
-    text: Vec<String>,
+    /// Oldest entry first, at most `capacity` entries are kept.
+    text: VecDeque<Entry>,
+    capacity: usize,
     text_params: TextParams,
     mesh: Mesh,
+    /// One-line box shown while collapsed.
+    ticker: Mesh,
+    /// Unit square scaled into the scrollbar thumb.
+    thumb: Mesh,
+    collapsed: bool,
+    /// Set while the box has keyboard focus, the arrow keys then move and resize it.
+    focused: bool,
     offset: usize,
+    margin: (f32, f32),
+    width: f32,
+    /// Number of lines shown at once, the box height follows from it.
+    lines: usize,
+    /// Vertical resize drag not yet large enough to add or remove a line.
+    stretch: f32,
+    visible: bool,
+    /// Built [`Text`] for the current messages, offset and color,
+    /// with the index of the entry shown on each line.
+    cache: Option<(Text, Vec<usize>)>,
+    /// Categories currently rendered.
+    shown: Vec<Category>,
+    /// Search query, entries not containing it are dimmed.
+    query: Option<String>,
+    /// Whether entries are prefixed with their [`Level::glyph`].
+    prefix: bool,
+    timestamps: Timestamps,
+    created: SystemTime,
+    /// Session log file every entry is appended to, if any.
+    file: Option<LineWriter<File>>,
+    /// Records from the `log` facade waiting to be added, see [`Log::install`].
+    forwarded: Option<Receiver<Forwarded>>,
 }
 
 /// Parameters for configuring the appearance of text in the log.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TextParams {
     color: [f32; 4],
     line_height: PxScale,
@@ -52,6 +363,16 @@ impl TextParams {
         self.line_height
     }
 
+    /// Changes the line height.
+    pub fn set_height(&mut self, line_height: f32) {
+        self.line_height = line_height.into();
+    }
+
+    /// Returns the line weight used for bounding boxes.
+    pub fn stroke_width(&self) -> f32 {
+        self.stroke_width
+    }
+
     /// Returns the color: [r, g, b, a]
     pub fn color(&self) -> &[f32; 4] {
         &self.color
@@ -59,7 +380,7 @@ impl TextParams {
 
     /// Change the color of the text.
     /// params:
-    /// new: [r, g, b, a]
+    /// color: [r, g, b, a]
     pub fn color_mut(&mut self, color: [f32; 4]) {
         self.color = color;
     }
@@ -67,96 +388,821 @@ impl TextParams {
 
 impl Log {
     const WIDTH: f32 = 400.0;
-    const HEIGHT: f32 = 88.0;
+    const MIN_WIDTH: f32 = 120.0;
+    /// Number of lines shown at once unless changed with [`Log::set_visible_lines`].
+    pub const DEFAULT_LINES: usize = 5;
+    /// Number of entries kept before the oldest are dropped.
+    pub const DEFAULT_CAPACITY: usize = 1000;
+    /// Space between the box and the text.
+    const PADDING: f32 = 5.0;
+    const PADDING_TOP: f32 = 2.0;
+    const SCROLLBAR_WIDTH: f32 = 3.0;
+    /// Distance from the border within which the mouse grabs the box.
+    const GRAB: f32 = 6.0;
     /// Creates a new [`Log`] instance.
-    pub fn new(params: TextParams, ctx: &impl Has<GraphicsContext>) -> Result<Log> {
+    ///
+    /// `margin` is the distance of the box from the bottom-left corner of the screen: (width, height).
+    pub fn new(
+        params: TextParams,
+        margin: (f32, f32),
+        ctx: &impl Has<GraphicsContext>,
+    ) -> Result<Log> {
+        let size = Vec2::new(Self::WIDTH, Self::box_height(&params, Self::DEFAULT_LINES));
+        let mesh = Self::build_mesh(ctx, &params, size)?;
+        let ticker = Self::build_mesh(ctx, &params, Self::ticker_size(&params, Self::WIDTH))?;
+        let thumb = Self::build_thumb(ctx, &params)?;
+
+        Ok(Self {
+            text: VecDeque::with_capacity(Self::DEFAULT_CAPACITY),
+            capacity: Self::DEFAULT_CAPACITY,
+            text_params: params,
+            offset: 0,
+            mesh,
+            ticker,
+            thumb,
+            collapsed: false,
+            focused: false,
+            margin,
+            width: Self::WIDTH,
+            lines: Self::DEFAULT_LINES,
+            stretch: 0.0,
+            visible: true,
+            cache: None,
+            shown: Category::ALL.to_vec(),
+            query: None,
+            prefix: true,
+            timestamps: Timestamps::Off,
+            created: SystemTime::now(),
+            file: None,
+            forwarded: None,
+        })
+    }
+
+    fn build_mesh(
+        ctx: &impl Has<GraphicsContext>,
+        params: &TextParams,
+        size: Vec2,
+    ) -> Result<Mesh> {
         let mut builder = graphics::MeshBuilder::new();
         builder.rectangle(
             graphics::DrawMode::stroke(params.stroke_width),
             graphics::Rect {
-                w: Self::WIDTH,
-                h: Self::HEIGHT,
+                w: size.x,
+                h: size.y,
                 ..Default::default()
             },
             graphics::Color::from(*params.color()),
         )?;
-        let mesh = Mesh::from_data(ctx, builder.build());
+        Ok(Mesh::from_data(ctx, builder.build()))
+    }
 
-        Ok(Self {
-            text: vec![],
-            text_params: params,
-            offset: 0,
-            mesh,
-        })
+    fn build_thumb(ctx: &impl Has<GraphicsContext>, params: &TextParams) -> Result<Mesh> {
+        let mut builder = graphics::MeshBuilder::new();
+        builder.rectangle(
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(0.0, 0.0, 1.0, 1.0),
+            graphics::Color::from(*params.color()),
+        )?;
+        Ok(Mesh::from_data(ctx, builder.build()))
+    }
+
+    /// Returns the scrollbar thumb inside a box of `size`: (top, height), or `None` if every entry fits.
+    ///
+    /// The newest entry is at the top of the box, so the thumb starts at the top and moves down
+    /// as the `offset` decreases. Only shown entries are counted.
+    fn thumb_span(&self, size: Vec2) -> Option<(f32, f32)> {
+        let len = self.text.len();
+        let total = self.shown_lines(..len);
+        let lines = self.visible_lines();
+        if self.collapsed || total <= lines {
+            return None;
+        }
+        let height = size.y * lines as f32 / total as f32;
+        let top = size.y * self.shown_lines(self.offset.min(len)..) as f32 / total as f32;
+        Some((top.min(size.y - height), height))
+    }
+
+    /// Height of a box holding `lines` lines of text.
+    fn box_height(params: &TextParams, lines: usize) -> f32 {
+        lines as f32 * params.height().y + Self::PADDING_TOP * 2.0
+    }
+
+    /// Size of the collapsed box, a single line `width` wide.
+    fn ticker_size(params: &TextParams, width: f32) -> Vec2 {
+        Vec2::new(width, Self::box_height(params, 1))
+    }
+
+    /// Returns the size of the expanded box.
+    fn size(&self) -> Vec2 {
+        Vec2::new(self.width, Self::box_height(&self.text_params, self.lines))
+    }
+
+    /// Returns the size of the box as currently drawn.
+    fn box_size(&self) -> Vec2 {
+        match self.collapsed {
+            true => Self::ticker_size(&self.text_params, self.width),
+            false => self.size(),
+        }
+    }
+
+    /// Returns true if the log is collapsed to a one-line ticker.
+    pub fn collapsed(&self) -> bool {
+        self.collapsed
+    }
+
+    /// Collapses the log to a one-line ticker showing the latest message, or expands it back.
+    pub fn set_collapsed(&mut self, collapsed: bool) {
+        self.collapsed = collapsed;
+        self.cache = None;
+    }
+
+    /// Returns true if the box has keyboard focus.
+    pub fn focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Gives or takes keyboard focus, a focused box moves and resizes with the arrow keys.
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+    }
+
+    /// Returns the message of the newest entry on screen, if any.
+    ///
+    /// Keyboard counterpart of [`Log::entry_at`].
+    pub fn top_entry(&self) -> Option<String> {
+        let end = match self.collapsed {
+            true => self.text.len(),
+            false => self.offset,
+        };
+        self.newest_shown(end)
+            .map(|index| self.text[index].message.clone())
+    }
+
+    /// Changes the height of the log text, the box grows or shrinks to keep the same line count.
+    pub fn set_text_height(
+        &mut self,
+        ctx: &impl Has<GraphicsContext>,
+        line_height: f32,
+    ) -> Result<()> {
+        self.text_params.set_height(line_height);
+        self.rebuild_meshes(ctx)?;
+        self.remeasure();
+        Ok(())
+    }
+
+    /// Returns the number of lines shown at once.
+    pub fn visible_lines(&self) -> usize {
+        self.lines
+    }
+
+    /// Changes the number of lines shown at once, at least one.
+    ///
+    /// The box height is derived from the line count, this rebuilds the mesh.
+    pub fn set_visible_lines(
+        &mut self,
+        ctx: &impl Has<GraphicsContext>,
+        lines: usize,
+    ) -> Result<()> {
+        self.lines = lines.max(1);
+        self.offset = self.offset.max(self.min_offset());
+        self.rebuild_meshes(ctx)?;
+        self.cache = None;
+        Ok(())
     }
 
     /// Sets the position of the log box on the screen, based on the provided offset: (width, height).
     pub fn set_box_position(&self, ctx: &impl Has<GraphicsContext>, offset: (f32, f32)) -> Vec2 {
         let (_, screen_height) = ctx.retrieve().drawable_size();
         let width = offset.0;
-        let height = screen_height - self.mesh.dimensions(ctx).unwrap().h - offset.1;
+        let height = screen_height - self.box_size().y - offset.1;
         (width, height).into()
     }
 
-    /// Returns a [`Text`] instance with the log messages, based on the current offset.
-    pub fn text(&mut self) -> Text {
+    /// Returns the log box in screen coordinates.
+    pub fn bounds(&self, ctx: &impl Has<GraphicsContext>) -> graphics::Rect {
+        let position = self.set_box_position(ctx, self.margin);
+        let size = self.box_size();
+        graphics::Rect::new(position.x, position.y, size.x, size.y)
+    }
+
+    /// Returns true if `point` (in screen coordinates) lies within the log box.
+    pub fn contains(&self, ctx: &impl Has<GraphicsContext>, point: Vec2) -> bool {
+        self.bounds(ctx).contains(point)
+    }
+
+    /// Returns the part of the box under `point` (in screen coordinates), if any.
+    ///
+    /// The collapsed ticker can only be moved.
+    pub fn handle_at(&self, ctx: &impl Has<GraphicsContext>, point: Vec2) -> Option<Handle> {
+        let bounds = self.bounds(ctx);
+        let corner = Vec2::new(bounds.right(), bounds.top());
+        if !self.collapsed && point.distance(corner) <= Self::GRAB * 2.0 {
+            return Some(Handle::Corner);
+        }
+
+        let outer = graphics::Rect::new(
+            bounds.x - Self::GRAB,
+            bounds.y - Self::GRAB,
+            bounds.w + Self::GRAB * 2.0,
+            bounds.h + Self::GRAB * 2.0,
+        );
+        let inner = graphics::Rect::new(
+            bounds.x + Self::GRAB,
+            bounds.y + Self::GRAB,
+            bounds.w - Self::GRAB * 2.0,
+            bounds.h - Self::GRAB * 2.0,
+        );
+        (outer.contains(point) && !inner.contains(point)).then_some(Handle::Border)
+    }
+
+    /// Moves the box by `delta` screen pixels.
+    pub fn move_by(&mut self, delta: Vec2) {
+        // the margin is measured from the bottom-left corner
+        self.margin.0 += delta.x;
+        self.margin.1 -= delta.y;
+    }
+
+    /// Resizes the box by dragging its top-right corner `delta` screen pixels.
+    ///
+    /// The bottom-left corner stays in place. The height snaps to whole lines,
+    /// smaller drags add up until they cover a line. This rebuilds the mesh and rewraps the text.
+    pub fn resize_by(&mut self, ctx: &impl Has<GraphicsContext>, delta: Vec2) -> Result<()> {
+        let line_height = self.text_params.height().y;
+        let width = (self.width + delta.x).max(Self::MIN_WIDTH);
+        if width != self.width {
+            self.width = width;
+            self.remeasure();
+        }
+        self.stretch -= delta.y;
+        let steps = (self.stretch / line_height).trunc();
+        self.stretch -= steps * line_height;
+        let lines = (self.lines as f32 + steps).max(1.0) as usize;
+        self.set_visible_lines(ctx, lines)
+    }
+
+    fn rebuild_meshes(&mut self, ctx: &impl Has<GraphicsContext>) -> Result<()> {
+        let ticker = Self::ticker_size(&self.text_params, self.width);
+        self.mesh = Self::build_mesh(ctx, &self.text_params, self.size())?;
+        self.ticker = Self::build_mesh(ctx, &self.text_params, ticker)?;
+        self.thumb = Self::build_thumb(ctx, &self.text_params)?;
+        Ok(())
+    }
+
+    /// Returns the [`Text`] with the log messages, based on the current offset.
+    ///
+    /// The text is only rebuilt after the messages, offset or color change.
+    /// Entries are measured first, so the offset stops where the oldest entry fits on screen.
+    pub fn text(&mut self, ctx: &impl Has<GraphicsContext>) -> &Text {
+        if self.cache.is_none() {
+            self.measure_entries(ctx);
+            self.offset = self.offset.max(self.min_offset());
+            self.cache = Some(self.build_text(ctx));
+        }
+        &self.cache.as_ref().unwrap().0
+    }
+
+    /// Returns the message of the entry drawn under `point` (in screen coordinates), if any.
+    pub fn entry_at(&mut self, ctx: &impl Has<GraphicsContext>, point: Vec2) -> Option<String> {
+        let bounds = self.bounds(ctx);
+        if !bounds.contains(point) {
+            return None;
+        }
+        self.text(ctx);
+        let row = (point.y - bounds.y - Self::PADDING_TOP) / self.text_params.height().y;
+        let (_, lines) = self.cache.as_ref()?;
+        let index = *lines.get(row.max(0.0) as usize)?;
+        self.text.get(index).map(|entry| entry.message.clone())
+    }
+
+    /// Builds the visible lines, newest entry on top.
+    ///
+    /// Entries are wrapped to the box width, at most [`Self::visible_lines`] lines are shown.
+    /// While collapsed, only the first line of the latest entry is shown.
+    /// Entries in hidden categories are skipped, entries not matching the search query are dimmed.
+    fn build_text(&self, ctx: &impl Has<GraphicsContext>) -> (Text, Vec<usize>) {
         let mut text = Text::default();
+        let mut line_entries = vec![];
+        let params = &self.text_params;
+        let (end, mut remaining) = match self.collapsed {
+            true => (self.text.len(), 1),
+            false => (self.offset.min(self.text.len()), self.visible_lines()),
+        };
+
+        let entries = self
+            .text
+            .range(..end)
+            .enumerate()
+            .rev()
+            .filter(|(_, entry)| self.shown.contains(&entry.category));
+        for (index, entry) in entries {
+            if remaining == 0 {
+                break;
+            }
+            let dimmed = self.searching() && !self.matches(entry);
+            let color = match dimmed {
+                true => Level::Debug.color(*params.color()),
+                false => entry.level.color(*params.color()),
+            };
+            let (stamp, glyph, lines) = self.layout(ctx, entry);
+            // continuation lines are indented past the prefix
+            let indent = " ".repeat(stamp.chars().count() + glyph.chars().count());
+
+            let runs = entry.runs(&lines);
+
+            for (i, line) in runs.iter().take(remaining).enumerate() {
+                if i == 0 {
+                    text.add(
+                        TextFragment::from(stamp.as_str())
+                            .font(params.font())
+                            .scale(params.height())
+                            .color(Tone::Muted.color(*params.color(), entry.level)),
+                    );
+                    text.add(
+                        TextFragment::from(glyph.as_str())
+                            .font(params.font())
+                            .scale(params.height())
+                            .color(color),
+                    );
+                } else {
+                    text.add(
+                        TextFragment::from(indent.as_str())
+                            .font(params.font())
+                            .scale(params.height()),
+                    );
+                }
+                for (run, tone) in line {
+                    let color = match dimmed {
+                        true => color,
+                        false => tone.color(*params.color(), entry.level),
+                    };
+                    text.add(
+                        TextFragment::from(run.as_str())
+                            .font(params.font())
+                            .scale(params.height())
+                            .color(color),
+                    );
+                }
+                text.add('\n').set_scale(params.height());
+                line_entries.push(index);
+            }
+            remaining = remaining.saturating_sub(lines.len());
+        }
+        (text, line_entries)
+    }
+
+    /// Splits `entry` into its timestamp, level glyph and message lines wrapped to the box width.
+    fn layout(
+        &self,
+        ctx: &impl Has<GraphicsContext>,
+        entry: &Entry,
+    ) -> (String, String, Vec<String>) {
+        let stamp = self
+            .timestamp(entry)
+            .map(|stamp| format!("[{stamp}] "))
+            .unwrap_or_default();
+        let glyph = match self.prefix {
+            true => format!("{} ", entry.level.glyph()),
+            false => String::new(),
+        };
+        let width =
+            self.width - Self::PADDING * 2.0 - self.measure(ctx, &format!("{stamp}{glyph}"));
+        let lines = self.wrap(ctx, &entry.message, width);
+        (stamp, glyph, lines)
+    }
+
+    /// Wraps the entries not measured since the last [`Log::remeasure`] and stores their height.
+    fn measure_entries(&mut self, ctx: &impl Has<GraphicsContext>) {
+        for index in 0..self.text.len() {
+            if self.text[index].height.is_none() {
+                let (_, _, lines) = self.layout(ctx, &self.text[index]);
+                self.text[index].height = Some(lines.len());
+            }
+        }
+    }
+
+    /// Forgets the measured entry heights after the box width or entry prefix changed.
+    fn remeasure(&mut self) {
+        for entry in &mut self.text {
+            entry.height = None;
+        }
+        self.cache = None;
+    }
+
+    /// Returns the width of `s` when drawn with the log's font.
+    fn measure(&self, ctx: &impl Has<GraphicsContext>, s: &str) -> f32 {
         let params = &self.text_params;
-        let vec_len = self.text.len();
-        let end = self.offset.min(vec_len).min(vec_len);
-        let start = match vec_len > 5 {
-            true => end.saturating_sub(5),
-            false => 0,
+        Text::new(
+            TextFragment::from(s)
+                .font(params.font())
+                .scale(params.height()),
+        )
+        .measure(ctx)
+        .map(|size| size.x)
+        .unwrap_or_default()
+    }
+
+    /// Splits `message` into lines no wider than `width`.
+    ///
+    /// Lines break between words, words that do not fit on a line of their own
+    /// are broken between characters.
+    fn wrap(&self, ctx: &impl Has<GraphicsContext>, message: &str, width: f32) -> Vec<String> {
+        let mut lines = vec![];
+        let mut line = String::new();
+
+        for word in message.split(' ') {
+            let candidate = match line.is_empty() {
+                true => word.to_string(),
+                false => format!("{line} {word}"),
+            };
+            if self.measure(ctx, &candidate) <= width {
+                line = candidate;
+                continue;
+            }
+            if !line.is_empty() {
+                lines.push(std::mem::take(&mut line));
+            }
+            for c in word.chars() {
+                line.push(c);
+                if line.chars().count() > 1 && self.measure(ctx, &line) > width {
+                    line.pop();
+                    lines.push(std::mem::take(&mut line));
+                    line.push(c);
+                }
+            }
+        }
+        if !line.is_empty() || lines.is_empty() {
+            lines.push(line);
+        }
+        lines
+    }
+
+    /// Adds a new message with the given [`Level`] to the log, under [`Category::System`].
+    ///
+    /// Adding a new message will reset the log `offset` to be max,
+    /// causing a jump to the top of the log.
+    pub fn push_with(&mut self, level: Level, s: String) {
+        self.push_in(Category::System, level, s);
+    }
+
+    /// Adds a new message with the given [`Category`] and [`Level`] to the log.
+    ///
+    /// Like [`Log::push_with`], this jumps to the top of the log.
+    pub fn push_in(&mut self, category: Category, level: Level, s: String) {
+        self.push_entry(Entry {
+            level,
+            category,
+            message: s,
+            tones: vec![],
+            time: SystemTime::now(),
+            height: None,
+        });
+    }
+
+    /// Adds a message made of differently colored [`Span`]s to the log.
+    ///
+    /// Like [`Log::push_with`], this jumps to the top of the log.
+    pub fn push_rich(&mut self, category: Category, level: Level, spans: Vec<Span>) {
+        let message = spans.iter().map(|span| span.text.as_str()).collect();
+        let tones = spans
+            .iter()
+            .flat_map(|span| span.text.chars().map(|_| span.tone))
+            .collect();
+        self.push_entry(Entry {
+            level,
+            category,
+            message,
+            tones,
+            time: SystemTime::now(),
+            height: None,
+        });
+    }
+
+    fn push_entry(&mut self, entry: Entry) {
+        self.write(&entry);
+        self.text.push_back(entry);
+        self.evict();
+        self.offset = self.text.len();
+        self.cache = None;
+    }
+
+    /// Appends every entry to the file at `path`, starting with the ones already logged.
+    ///
+    /// The file is created if needed, earlier sessions are kept.
+    pub fn mirror_to(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let mut file = LineWriter::new(OpenOptions::new().create(true).append(true).open(path)?);
+        writeln!(
+            file,
+            "--- session started {} UTC ---",
+            DateTime::from(self.created).iso()
+        )?;
+        self.file = Some(file);
+        let entries: Vec<Entry> = self.text.iter().cloned().collect();
+        for entry in &entries {
+            self.write(entry);
+        }
+        Ok(())
+    }
+
+    /// Appends `entry` to the session log file.
+    ///
+    /// Mirroring stops at the first failed write, the on-screen log keeps working.
+    fn write(&mut self, entry: &Entry) {
+        let Some(file) = &mut self.file else {
+            return;
+        };
+        let line = format!(
+            "{} [{:?}] [{}] {}",
+            DateTime::from(entry.time).iso(),
+            entry.level,
+            entry.category.name(),
+            entry.message
+        );
+        if writeln!(file, "{line}").is_err() {
+            self.file = None;
+        }
+    }
+
+    /// Registers the log as the backend of the `log` facade, so `log::info!` and friends
+    /// from this crate and `cartography_core` show up on screen.
+    ///
+    /// Records up to `level` are kept, other crates are limited to warnings and errors.
+    /// Forwarded records appear after the next [`Log::drain`].
+    /// Fails if a logger was already installed.
+    pub fn install(&mut self, level: log::LevelFilter) -> Result<()> {
+        let (sender, receiver) = mpsc::channel();
+        log::set_boxed_logger(Box::new(Bridge {
+            sender: Mutex::new(sender),
+            level,
+        }))
+        .map_err(|e| GameError::CustomError(format!("could not install logger: {e}")))?;
+        log::set_max_level(level.max(log::LevelFilter::Warn));
+        self.forwarded = Some(receiver);
+        Ok(())
+    }
+
+    /// Adds the records forwarded from the `log` facade since the last call.
+    ///
+    /// Returns true if any record was added.
+    pub fn drain(&mut self) -> bool {
+        let records: Vec<Forwarded> = match &self.forwarded {
+            Some(receiver) => receiver.try_iter().collect(),
+            None => return false,
         };
+        let added = !records.is_empty();
+        for (level, category, message) in records {
+            self.push_in(category, level, message);
+        }
+        added
+    }
+
+    /// Changes the maximum number of entries kept, at least one.
+    ///
+    /// The oldest entries are dropped if the log holds more than `capacity`.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        self.evict();
+        self.cache = None;
+    }
+
+    /// Drops the oldest entries beyond `capacity`.
+    ///
+    /// The `offset` counts entries from the oldest one, so it moves down with them
+    /// to keep the same messages on screen.
+    fn evict(&mut self) {
+        let excess = self.text.len().saturating_sub(self.capacity);
+        if excess == 0 {
+            return;
+        }
+        self.text.drain(..excess);
+        self.offset = self.offset.saturating_sub(excess).max(self.min_offset());
+    }
+
+    /// Adds a [`Level::Warn`] message to the log.
+    pub fn warn(&mut self, s: String) {
+        self.push_with(Level::Warn, s);
+    }
+
+    /// Adds a [`Level::Error`] message to the log.
+    pub fn error(&mut self, s: String) {
+        self.push_with(Level::Error, s);
+    }
+
+    /// Changes how entry times are shown.
+    pub fn set_timestamps(&mut self, timestamps: Timestamps) {
+        self.timestamps = timestamps;
+        self.remeasure();
+    }
+
+    fn timestamp(&self, entry: &Entry) -> Option<String> {
+        match self.timestamps {
+            Timestamps::Off => None,
+            Timestamps::Clock => Some(DateTime::from(entry.time).clock()),
+            Timestamps::Relative => {
+                let secs = entry
+                    .time
+                    .duration_since(self.created)
+                    .unwrap_or_default()
+                    .as_secs();
+                Some(match secs >= 3600 {
+                    true => format!("+{}:{:02}:{:02}", secs / 3600, secs % 3600 / 60, secs % 60),
+                    false => format!("+{:02}:{:02}", secs / 60, secs % 60),
+                })
+            }
+        }
+    }
+
+    /// Shows or hides the level glyph before each entry.
+    pub fn set_prefix(&mut self, prefix: bool) {
+        self.prefix = prefix;
+        self.remeasure();
+    }
+
+    /// Returns whether entries in `category` are rendered.
+    pub fn shown(&self, category: Category) -> bool {
+        self.shown.contains(&category)
+    }
+
+    /// Shows or hides entries in `category`.
+    pub fn set_shown(&mut self, category: Category, shown: bool) {
+        self.shown.retain(|c| *c != category);
+        if shown {
+            self.shown.push(category);
+        }
+        self.offset = self.offset.max(self.min_offset());
+        self.cache = None;
+    }
+
+    /// Returns the search query, if any.
+    pub fn query(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    /// Changes the search query, `None` ends the search.
+    pub fn set_query(&mut self, query: Option<String>) {
+        self.query = query;
+        self.cache = None;
+    }
+
+    /// Returns true if a non-empty search query is set.
+    fn searching(&self) -> bool {
+        self.query.as_deref().is_some_and(|query| !query.is_empty())
+    }
+
+    /// Returns true if `entry` contains the search query, ignoring case.
+    fn matches(&self, entry: &Entry) -> bool {
+        match &self.query {
+            Some(query) => entry.message.to_lowercase().contains(&query.to_lowercase()),
+            None => false,
+        }
+    }
+
+    /// Returns the indices of the shown entries matching the search query, oldest first.
+    fn match_indices(&self) -> Vec<usize> {
+        if !self.searching() {
+            return vec![];
+        }
+        self.text
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| self.shown.contains(&entry.category) && self.matches(entry))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Moves the offset so that `index` is the newest entry on screen.
+    fn jump_to(&mut self, index: usize) {
+        self.offset = (index + 1).max(self.min_offset());
+        self.cache = None;
+    }
+
+    /// Jumps to the newest match, returns false if nothing matches.
+    pub fn first_match(&mut self) -> bool {
+        match self.match_indices().last() {
+            Some(&index) => {
+                self.jump_to(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Jumps to the next older match below the top entry, returns false if there is none.
+    pub fn next_match(&mut self) -> bool {
+        let top = self.newest_shown(self.offset).unwrap_or_default();
+        match self.match_indices().into_iter().rev().find(|i| *i < top) {
+            Some(index) => {
+                self.jump_to(index);
+                true
+            }
+            None => false,
+        }
+    }
 
-        for i in (start..end).rev() {
-            text.add(
-                TextFragment::from(&self.text[i])
+    /// Jumps to the next newer match above the top entry, returns false if there is none.
+    pub fn prev_match(&mut self) -> bool {
+        let top = self.newest_shown(self.offset).unwrap_or_default();
+        match self.match_indices().into_iter().find(|i| *i > top) {
+            Some(index) => {
+                self.jump_to(index);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Builds the header listing every category, hidden ones are dimmed.
+    fn header(&self) -> Text {
+        let params = &self.text_params;
+        let mut header = Text::default();
+        for category in Category::ALL {
+            let color = match self.shown(category) {
+                true => *params.color(),
+                false => Level::Debug.color(*params.color()),
+            };
+            header.add(
+                TextFragment::from(format!("[{}] ", category.name()))
+                    .font(params.font())
+                    .scale(params.height())
+                    .color(color),
+            );
+        }
+        if let Some(query) = &self.query {
+            header.add(
+                TextFragment::from(format!("/{query}"))
                     .font(params.font())
                     .scale(params.height())
                     .color(*params.color()),
             );
-            text.add('\n').set_scale(params.height());
         }
-        text
+        header
+    }
+
+    /// Returns the index of the newest shown entry before `end`, if any.
+    fn newest_shown(&self, end: usize) -> Option<usize> {
+        self.text
+            .range(..end.min(self.text.len()))
+            .rposition(|entry| self.shown.contains(&entry.category))
     }
 
-    /// Adds a new message to the log
+    /// Returns the number of wrapped lines taken by the shown entries in `range`.
     ///
-    /// Adding a new message will reset the log `offset` to be max,
-    /// causing a jump to the top of the log.
-    pub fn push(&mut self, s: String) {
-        self.text.push(s);
-        self.offset = self.text.len();
+    /// Entries not measured yet count as one line.
+    fn shown_lines(&self, range: impl RangeBounds<usize>) -> usize {
+        self.text
+            .range(range)
+            .filter(|entry| self.shown.contains(&entry.category))
+            .map(|entry| entry.height.unwrap_or(1))
+            .sum()
     }
 
-    /// Returns a clone of the log's [`Mesh`].
-    pub fn mesh(&self) -> Mesh {
-        self.mesh.clone()
+    /// Returns the lowest `offset`, at which the oldest shown entries fill the box.
+    ///
+    /// The oldest shown entry is always kept on screen, so scrolling stops once its last line
+    /// reaches the bottom of the box.
+    fn min_offset(&self) -> usize {
+        let mut used = 0;
+        let mut floor = 0;
+        for (index, entry) in self
+            .text
+            .iter()
+            .enumerate()
+            .filter(|(_, entry)| self.shown.contains(&entry.category))
+        {
+            used += entry.height.unwrap_or(1);
+            if used > self.visible_lines() && floor > 0 {
+                break;
+            }
+            floor = index + 1;
+        }
+        floor
     }
 
-    /// Increments the log's offset by one, if
-    /// - the offset is less than the number of log messages.
+    /// Scrolls up by one entry, skipping hidden categories.
     ///
-    /// the offset cannot be increased past the length of the text vector
+    /// Does nothing once the newest shown entry is on top.
     pub fn incr_offset(&mut self) {
-        if self.offset < self.text.len() {
-            self.offset += 1;
+        let newer = (self.offset..self.text.len())
+            .find(|&index| self.shown.contains(&self.text[index].category));
+        if let Some(index) = newer {
+            self.offset = index + 1;
+            self.cache = None;
         }
     }
 
-    /// Decrements the log's `offset` by one, if:
-    /// - the `offset` is greater than 0 and the number of log messages is greater than 5
-    /// **OR**
-    /// - the `offset` is less than or equal to 5.
+    /// Scrolls down by one entry, skipping hidden categories.
     ///
-    /// This allows the `offset` to be decremented only when number of elements
-    /// exceed the amount of elements to be shown (five at a time.)
+    /// Does nothing once the oldest shown entry is at the bottom of the box, see [`Self::min_offset`].
     pub fn decr_offset(&mut self) {
-        let len = self.text.len();
-        if (len > 5 && self.offset == 5) || (self.text.len() <= 5 && self.offset <= 5) {
-        } else if self.offset > 0 {
-            self.offset -= 1;
+        let Some(top) = self.newest_shown(self.offset) else {
+            return;
+        };
+        let offset = match self.newest_shown(top) {
+            Some(below) => (below + 1).max(self.min_offset()),
+            None => self.offset,
+        };
+        if offset < self.offset {
+            self.offset = offset;
+            self.cache = None;
         }
     }
 
@@ -166,20 +1212,66 @@ impl Log {
     /// params
     /// - color: [r, g, b, a]
     pub fn color_mut(&mut self, ctx: &impl Has<GraphicsContext>, color: [f32; 4]) -> Result<()> {
-        let mut builder = graphics::MeshBuilder::new();
         self.text_params.color_mut(color);
-        let params = &self.text_params;
-        builder.rectangle(
-            graphics::DrawMode::stroke(params.stroke_width),
-            graphics::Rect {
-                w: Self::WIDTH,
-                h: Self::HEIGHT,
-                ..Default::default()
-            },
-            graphics::Color::from(*params.color()),
-        )?;
-        let mesh = Mesh::from_data(ctx, builder.build());
-        self.mesh = mesh;
+        self.rebuild_meshes(ctx)?;
+        self.cache = None;
+        Ok(())
+    }
+}
+
+impl Layer for Log {
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    fn kind(&self) -> LayerKind {
+        LayerKind::Ui
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Draws the box anchored to the bottom-left of the canvas, so exports that include
+    /// the UI place it the same way as the window.
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
+        let bounds = canvas_bounds(ctx, canvas);
+        let box_offset = Vec2::new(
+            bounds.left() + self.margin.0,
+            bounds.bottom() - self.box_size().y - self.margin.1,
+        );
+        let text_offset = box_offset + Vec2::new(Self::PADDING, Self::PADDING_TOP);
+
+        // the header sits just above the box
+        let header_offset =
+            box_offset - Vec2::new(0.0, self.text_params.height().y + Self::PADDING_TOP);
+
+        canvas.draw(&self.header(), DrawParam::default().dest(header_offset));
+        canvas.draw(self.text(ctx), DrawParam::default().dest(text_offset));
+        let mesh = match self.collapsed {
+            true => &self.ticker,
+            false => &self.mesh,
+        };
+        canvas.draw(mesh, DrawParam::default().dest(box_offset));
+
+        // the scrollbar runs along the inside of the right edge
+        if let Some((top, height)) = self.thumb_span(self.size()) {
+            let x = self.width - Self::SCROLLBAR_WIDTH - self.text_params.stroke_width;
+            canvas.draw(
+                &self.thumb,
+                DrawParam::default()
+                    .dest(box_offset + Vec2::new(x, top))
+                    .scale(Vec2::new(Self::SCROLLBAR_WIDTH, height)),
+            );
+        }
         Ok(())
     }
+
+    fn focus_bounds(&self, ctx: &Context, _canvas: &Canvas) -> GameResult<graphics::Rect> {
+        Ok(self.bounds(ctx))
+    }
 }
@@ -1,5 +1,19 @@
 use ggez::GameError;
 
+pub mod camera;
+pub mod cartouche;
+pub mod export;
+pub mod grid;
+pub mod layers;
+pub mod legend;
+pub mod lighting;
 pub mod logger;
+pub mod parchment;
+pub mod particles;
+pub mod pdf;
+pub mod post;
+pub mod save;
+pub mod swatches;
+pub mod transition;
 
 pub type Result<T> = ::std::result::Result<T, GameError>;
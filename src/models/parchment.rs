@@ -0,0 +1,114 @@
+use std::str::FromStr;
+
+use ggez::{
+    glam::Vec2,
+    graphics::{self, Canvas, ClampMode, DrawParam, Image, Rect, Sampler},
+    Context, GameResult,
+};
+
+use super::{
+    layers::{canvas_bounds, Layer, LayerKind},
+    Result,
+};
+
+/// How the parchment texture covers the screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fit {
+    /// Repeat the texture at its native size.
+    Tile,
+    /// Stretch a single copy of the texture over the whole screen.
+    Stretch,
+}
+
+impl FromStr for Fit {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "tile" => Ok(Fit::Tile),
+            "stretch" => Ok(Fit::Stretch),
+            _ => Err(format!("unknown parchment fit: {s}")),
+        }
+    }
+}
+
+/// Textured background drawn behind the map, tinted with the palette.
+#[derive(Debug)]
+pub struct Parchment {
+    image: Image,
+    tint: [f32; 4],
+    fit: Fit,
+    visible: bool,
+}
+
+impl Parchment {
+    /// Loads the parchment texture from the resource directory.
+    /// Parameters:
+    /// - `path`: path of the texture as seen by ggez, e.g. `/parchment.png`
+    /// - `tint`: [r, g, b, a], multiplied with the texture
+    /// - `fit`: tile or stretch the texture
+    pub fn load(ctx: &Context, path: &str, tint: [f32; 4], fit: Fit) -> Result<Parchment> {
+        let image = Image::from_path(ctx, path)?;
+        Ok(Self {
+            image,
+            tint,
+            fit,
+            visible: false,
+        })
+    }
+
+    /// Change the tint of the texture.
+    /// params:
+    /// tint: [r, g, b, a]
+    pub fn color_mut(&mut self, tint: [f32; 4]) {
+        self.tint = tint;
+    }
+}
+
+impl Layer for Parchment {
+    fn name(&self) -> &str {
+        "parchment"
+    }
+
+    fn kind(&self) -> LayerKind {
+        LayerKind::Background
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    fn draw(&mut self, ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
+        let bounds = canvas_bounds(ctx, canvas);
+        let (width, height) = (bounds.w, bounds.h);
+        let size = Vec2::new(self.image.width() as f32, self.image.height() as f32);
+        let param = DrawParam::default().color(graphics::Color::from(self.tint));
+
+        match self.fit {
+            Fit::Tile => {
+                // uv coordinates past 1.0 repeat the texture
+                canvas.set_sampler(Sampler {
+                    clamp_u: ClampMode::Repeat,
+                    clamp_v: ClampMode::Repeat,
+                    ..Sampler::linear_clamp()
+                });
+                canvas.draw(
+                    &self.image,
+                    param.src(Rect::new(0.0, 0.0, width / size.x, height / size.y)),
+                );
+                canvas.set_default_sampler();
+            }
+            Fit::Stretch => {
+                canvas.draw(
+                    &self.image,
+                    param.scale(Vec2::new(width / size.x, height / size.y)),
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,147 @@
+use ggez::{
+    context::Has,
+    glam::Vec2,
+    graphics::{Canvas, Color, DrawParam, GraphicsContext, InstanceArray},
+    Context, GameResult,
+};
+
+use super::layers::{Layer, LayerKind};
+
+/// A single particle managed by a [`ParticleSystem`].
+#[derive(Debug, Clone, Copy)]
+pub struct Particle {
+    position: Vec2,
+    velocity: Vec2,
+    acceleration: Vec2,
+    color: Color,
+    size: f32,
+    life: f32,
+    lifetime: f32,
+}
+
+impl Particle {
+    /// Creates a new [`Particle`].
+    /// Parameters:
+    /// - `position`: starting position in world space
+    /// - `velocity`: units per second
+    /// - `color`: starting color, the alpha fades out over the lifetime
+    /// - `size`: width and height of the particle
+    /// - `lifetime`: seconds until the particle is removed
+    pub fn new(position: Vec2, velocity: Vec2, color: Color, size: f32, lifetime: f32) -> Particle {
+        Self {
+            position,
+            velocity,
+            acceleration: Vec2::ZERO,
+            color,
+            size,
+            life: lifetime,
+            lifetime,
+        }
+    }
+
+    /// Sets a constant acceleration, e.g. gravity for rain or spray.
+    pub fn with_acceleration(mut self, acceleration: Vec2) -> Particle {
+        self.acceleration = acceleration;
+        self
+    }
+
+    fn draw_param(&self) -> DrawParam {
+        let mut color = self.color;
+        color.a *= (self.life / self.lifetime).clamp(0.0, 1.0);
+        DrawParam::default()
+            .dest(self.position - Vec2::splat(self.size / 2.0))
+            .scale(Vec2::splat(self.size))
+            .color(color)
+    }
+}
+
+/// Pooled particle system used by ambient effects.
+///
+/// Particles are stored in a fixed-size pool, new particles are dropped
+/// once it is full. All live particles are drawn with a single instanced draw call.
+#[derive(Debug)]
+pub struct ParticleSystem {
+    pool: Vec<Particle>,
+    capacity: usize,
+    instances: InstanceArray,
+    visible: bool,
+}
+
+impl ParticleSystem {
+    /// Creates a new [`ParticleSystem`] holding at most `capacity` particles.
+    pub fn new(ctx: &impl Has<GraphicsContext>, capacity: usize) -> ParticleSystem {
+        Self {
+            pool: Vec::with_capacity(capacity),
+            capacity,
+            instances: InstanceArray::new(ctx, None),
+            visible: true,
+        }
+    }
+
+    /// Returns true if there are no live particles.
+    pub fn is_empty(&self) -> bool {
+        self.pool.is_empty()
+    }
+
+    /// Adds a particle to the pool.
+    ///
+    /// Returns false if the pool is full and the particle was dropped.
+    pub fn emit(&mut self, particle: Particle) -> bool {
+        if self.pool.len() >= self.capacity {
+            return false;
+        }
+        self.pool.push(particle);
+        true
+    }
+
+    /// Advances all particles by `dt` seconds and removes expired ones.
+    pub fn update(&mut self, dt: f32) {
+        let mut i = 0;
+        while i < self.pool.len() {
+            let particle = &mut self.pool[i];
+            particle.life -= dt;
+            if particle.life <= 0.0 {
+                // order does not matter, avoid shifting the pool
+                self.pool.swap_remove(i);
+                continue;
+            }
+            particle.velocity += particle.acceleration * dt;
+            particle.position += particle.velocity * dt;
+            i += 1;
+        }
+    }
+
+    /// Removes all particles.
+    pub fn clear(&mut self) {
+        self.pool.clear();
+    }
+}
+
+impl Layer for ParticleSystem {
+    fn name(&self) -> &str {
+        "particles"
+    }
+
+    fn kind(&self) -> LayerKind {
+        LayerKind::Features
+    }
+
+    fn visible(&self) -> bool {
+        self.visible
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        self.visible = visible;
+    }
+
+    /// Draws all live particles with a single instanced draw call.
+    fn draw(&mut self, _ctx: &mut Context, canvas: &mut Canvas) -> GameResult {
+        if self.pool.is_empty() {
+            return Ok(());
+        }
+        self.instances
+            .set(self.pool.iter().map(Particle::draw_param));
+        canvas.draw(&self.instances, DrawParam::default());
+        Ok(())
+    }
+}
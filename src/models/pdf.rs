@@ -0,0 +1,234 @@
+use std::{fs, io::Write, path::Path, str::FromStr};
+
+use flate2::{write::ZlibEncoder, Compression};
+use ggez::{GameError, GameResult};
+
+/// Paper size of printed pages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageSize {
+    Letter,
+    A4,
+}
+
+impl PageSize {
+    /// Returns the page size in inches: (width, height), portrait.
+    pub fn inches(&self) -> (f32, f32) {
+        match self {
+            PageSize::Letter => (8.5, 11.0),
+            PageSize::A4 => (8.27, 11.69),
+        }
+    }
+}
+
+impl FromStr for PageSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "letter" => Ok(PageSize::Letter),
+            "a4" => Ok(PageSize::A4),
+            _ => Err(format!("unknown page size: {s}")),
+        }
+    }
+}
+
+/// An RGBA8 image placed on its own page.
+#[derive(Debug)]
+pub struct Page {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+    /// Position of the image's top-left corner from the top-left of the page, in inches: (x, y)
+    pub position: (f32, f32),
+    /// Printed size of the image in inches: (width, height)
+    pub size: (f32, f32),
+}
+
+/// PDF units per inch.
+const POINTS: f32 = 72.0;
+
+/// Highest print resolution accepted, finer prints only make the export larger.
+pub const MAX_DPI: u32 = 1200;
+
+/// Writes `pages` as a PDF document with one image per page.
+///
+/// Images are stored as compressed RGB, alpha is dropped.
+pub fn save(pages: &[Page], page_size: PageSize, path: impl AsRef<Path>) -> GameResult {
+    let (page_width, page_height) = page_size.inches();
+    let (page_width, page_height) = (page_width * POINTS, page_height * POINTS);
+
+    let mut out: Vec<u8> = b"%PDF-1.4\n".to_vec();
+    let mut offsets = vec![];
+    let mut object = |out: &mut Vec<u8>, body: &[u8]| {
+        offsets.push(out.len());
+        out.extend_from_slice(format!("{} 0 obj\n", offsets.len()).as_bytes());
+        out.extend_from_slice(body);
+        out.extend_from_slice(b"\nendobj\n");
+    };
+
+    // objects 1 and 2 are the catalog and page tree, every page takes three more
+    let kids: Vec<String> = (0..pages.len())
+        .map(|i| format!("{} 0 R", 3 + i * 3))
+        .collect();
+    object(&mut out, b"<< /Type /Catalog /Pages 2 0 R >>");
+    object(
+        &mut out,
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            kids.join(" "),
+            pages.len()
+        )
+        .as_bytes(),
+    );
+
+    for (i, page) in pages.iter().enumerate() {
+        let id = 3 + i * 3;
+        let (x, y) = (page.position.0 * POINTS, page.position.1 * POINTS);
+        let (w, h) = (page.size.0 * POINTS, page.size.1 * POINTS);
+
+        object(
+            &mut out,
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {page_width} {page_height}] \
+                 /Resources << /XObject << /Im0 {} 0 R >> >> /Contents {} 0 R >>",
+                id + 2,
+                id + 1
+            )
+            .as_bytes(),
+        );
+
+        // PDF measures from the bottom-left corner
+        let contents = format!("q {w} 0 0 {h} {x} {} cm /Im0 Do Q", page_height - y - h);
+        object(&mut out, &stream("", contents.as_bytes()));
+
+        let rgb: Vec<u8> = page
+            .pixels
+            .chunks_exact(4)
+            .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+            .collect();
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(&rgb)?;
+        let data = encoder.finish()?;
+        object(
+            &mut out,
+            &stream(
+                &format!(
+                    "/Type /XObject /Subtype /Image /Width {} /Height {} \
+                     /ColorSpace /DeviceRGB /BitsPerComponent 8 /Filter /FlateDecode",
+                    page.width, page.height
+                ),
+                &data,
+            ),
+        );
+    }
+
+    let xref = out.len();
+    out.extend_from_slice(format!("xref\n0 {}\n", offsets.len() + 1).as_bytes());
+    out.extend_from_slice(b"0000000000 65535 f \n");
+    for offset in &offsets {
+        out.extend_from_slice(format!("{offset:010} 00000 n \n").as_bytes());
+    }
+    out.extend_from_slice(
+        format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            offsets.len() + 1
+        )
+        .as_bytes(),
+    );
+
+    fs::write(path, out).map_err(|e| GameError::CustomError(format!("could not write pdf: {e}")))
+}
+
+/// Builds a stream object body with the extra dictionary `entries`.
+fn stream(entries: &str, data: &[u8]) -> Vec<u8> {
+    let mut body = format!("<< {entries} /Length {} >>\nstream\n", data.len()).into_bytes();
+    body.extend_from_slice(data);
+    body.extend_from_slice(b"\nendstream");
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, io::Read};
+
+    use flate2::read::ZlibDecoder;
+
+    use super::*;
+
+    /// Returns the position of the first `needle` in `bytes` at or after `from`.
+    fn find(bytes: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+        bytes[from..]
+            .windows(needle.len())
+            .position(|window| window == needle)
+            .map(|i| from + i)
+    }
+
+    /// Saves two small pages and returns the file contents.
+    fn sample() -> Vec<u8> {
+        let page = |width: u32, height: u32| Page {
+            width,
+            height,
+            pixels: [10, 20, 30, 255].repeat((width * height) as usize),
+            position: (0.5, 0.5),
+            size: (width as f32, height as f32),
+        };
+        let path = env::temp_dir().join(format!("cartographer-pdf-{}.pdf", std::process::id()));
+        save(&[page(2, 3), page(1, 1)], PageSize::A4, &path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        bytes
+    }
+
+    #[test]
+    fn xref_points_at_every_object() {
+        let pdf = sample();
+        assert!(pdf.starts_with(b"%PDF-1.4\n"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+
+        let start = find(&pdf, b"startxref\n", 0).unwrap() + b"startxref\n".len();
+        let end = find(&pdf, b"\n", start).unwrap();
+        let xref: usize = std::str::from_utf8(&pdf[start..end])
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(pdf[xref..].starts_with(b"xref\n0 9\n0000000000 65535 f \n"));
+
+        // catalog, page tree and three objects per page, each entry 20 bytes long
+        let entries = xref + b"xref\n0 9\n".len() + 20;
+        for id in 1..=8 {
+            let entry = &pdf[entries + (id - 1) * 20..entries + id * 20];
+            assert!(entry.ends_with(b" 00000 n \n"));
+            let offset: usize = std::str::from_utf8(&entry[..10]).unwrap().parse().unwrap();
+            assert!(pdf[offset..].starts_with(format!("{id} 0 obj\n").as_bytes()));
+        }
+    }
+
+    #[test]
+    fn stream_lengths_match_their_data() {
+        let pdf = sample();
+        let mut images = vec![];
+        let mut from = 0;
+        while let Some(at) = find(&pdf, b"/Length ", from) {
+            let start = at + b"/Length ".len();
+            let end = find(&pdf, b" >>\nstream\n", start).unwrap();
+            let length: usize = std::str::from_utf8(&pdf[start..end])
+                .unwrap()
+                .parse()
+                .unwrap();
+            let data = end + b" >>\nstream\n".len();
+            assert!(pdf[data + length..].starts_with(b"\nendstream"));
+            if pdf[..at].ends_with(b"/FlateDecode ") {
+                images.push(pdf[data..data + length].to_vec());
+            }
+            from = data + length;
+        }
+
+        // alpha is dropped from the compressed pixels
+        assert_eq!(images.len(), 2);
+        let mut rgb = vec![];
+        ZlibDecoder::new(images[0].as_slice())
+            .read_to_end(&mut rgb)
+            .unwrap();
+        assert_eq!(rgb, [10, 20, 30].repeat(6));
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+
+use crevice::std140::AsStd140;
+use ggez::{
+    graphics::{
+        Canvas, Color, DrawParam, Image, ScreenImage, Shader, ShaderBuilder, ShaderParams,
+        ShaderParamsBuilder,
+    },
+    Context, GameResult,
+};
+use serde::{Deserialize, Serialize};
+
+/// Post-processing effects, applied in declaration order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Effect {
+    Sepia,
+    Grain,
+    Vignette,
+}
+
+impl Effect {
+    /// All effects, in the order they are applied.
+    pub const ALL: [Effect; 3] = [Effect::Sepia, Effect::Grain, Effect::Vignette];
+
+    fn shader_path(&self) -> &'static str {
+        match self {
+            Effect::Sepia => "/sepia.wgsl",
+            Effect::Grain => "/grain.wgsl",
+            Effect::Vignette => "/vignette.wgsl",
+        }
+    }
+}
+
+/// Uniforms shared by every effect shader, see `resources/*.wgsl`.
+#[derive(AsStd140)]
+struct Uniforms {
+    strength: f32,
+    time: f32,
+    width: f32,
+    height: f32,
+}
+
+struct Pass {
+    effect: Effect,
+    shader: Shader,
+    params: ShaderParams<Uniforms>,
+    strength: f32,
+    enabled: bool,
+}
+
+/// Chain of optional full-screen shader passes applied to the rendered scene.
+pub struct PostProcess {
+    passes: Vec<Pass>,
+    /// Ping-pong render targets, each pass reads from one and writes to the other.
+    targets: [ScreenImage; 2],
+    /// Whether time-based effects such as grain move between frames.
+    animated: bool,
+}
+
+impl PostProcess {
+    /// Compiles the effect shaders from the resource directory.
+    ///
+    /// Every effect starts disabled.
+    pub fn new(ctx: &mut Context) -> GameResult<PostProcess> {
+        let passes = Effect::ALL
+            .into_iter()
+            .map(|effect| {
+                let shader = ShaderBuilder::new()
+                    .fragment_path(effect.shader_path())
+                    .build(&ctx.gfx)?;
+                let params = ShaderParamsBuilder::new(&Uniforms {
+                    strength: 1.0,
+                    time: 0.0,
+                    width: 1.0,
+                    height: 1.0,
+                })
+                .build(ctx);
+                Ok(Pass {
+                    effect,
+                    shader,
+                    params,
+                    strength: 1.0,
+                    enabled: false,
+                })
+            })
+            .collect::<GameResult<Vec<Pass>>>()?;
+
+        Ok(Self {
+            passes,
+            targets: [
+                ScreenImage::new(ctx, None, 1.0, 1.0, 1),
+                ScreenImage::new(ctx, None, 1.0, 1.0, 1),
+            ],
+            animated: true,
+        })
+    }
+
+    /// Returns whether `effect` is applied.
+    pub fn enabled(&self, effect: Effect) -> bool {
+        self.pass(effect).enabled
+    }
+
+    /// Enables or disables `effect`.
+    pub fn set_enabled(&mut self, effect: Effect, enabled: bool) {
+        self.pass_mut(effect).enabled = enabled;
+    }
+
+    /// Returns the strength of `effect`, between 0 and 1.
+    pub fn strength(&self, effect: Effect) -> f32 {
+        self.pass(effect).strength
+    }
+
+    /// Changes the strength of `effect`, clamped between 0 and 1.
+    pub fn set_strength(&mut self, effect: Effect, strength: f32) {
+        self.pass_mut(effect).strength = strength.clamp(0.0, 1.0);
+    }
+
+    /// Returns whether time-based effects change between frames.
+    pub fn animated(&self) -> bool {
+        self.animated
+    }
+
+    /// Freezes or resumes time-based effects, for reduced motion.
+    pub fn set_animated(&mut self, animated: bool) {
+        self.animated = animated;
+    }
+
+    /// Runs the enabled effects over `input` and returns the final image.
+    ///
+    /// Returns `input` unchanged if no effect is enabled.
+    pub fn apply(&mut self, ctx: &mut Context, input: Image) -> GameResult<Image> {
+        let time = match self.animated {
+            true => ctx.time.time_since_start().as_secs_f32(),
+            false => 0.0,
+        };
+        let (width, height) = ctx.gfx.drawable_size();
+        let targets = &mut self.targets;
+
+        let mut current = input;
+        for (i, pass) in self.passes.iter().filter(|pass| pass.enabled).enumerate() {
+            pass.params.set_uniforms(
+                ctx,
+                &Uniforms {
+                    strength: pass.strength,
+                    time,
+                    width,
+                    height,
+                },
+            );
+
+            let target = &mut targets[i % 2];
+            let mut canvas = Canvas::from_screen_image(ctx, target, Color::BLACK);
+            canvas.set_shader(&pass.shader);
+            canvas.set_shader_params(&pass.params);
+            canvas.draw(&current, DrawParam::default());
+            canvas.finish(ctx)?;
+            current = target.image(ctx);
+        }
+        Ok(current)
+    }
+
+    fn pass(&self, effect: Effect) -> &Pass {
+        // every effect gets a pass in `new`
+        self.passes
+            .iter()
+            .find(|pass| pass.effect == effect)
+            .unwrap()
+    }
+
+    fn pass_mut(&mut self, effect: Effect) -> &mut Pass {
+        self.passes
+            .iter_mut()
+            .find(|pass| pass.effect == effect)
+            .unwrap()
+    }
+}
+
+impl fmt::Debug for PostProcess {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let enabled: Vec<Effect> = self
+            .passes
+            .iter()
+            .filter(|pass| pass.enabled)
+            .map(|pass| pass.effect)
+            .collect();
+        f.debug_struct("PostProcess")
+            .field("enabled", &enabled)
+            .finish_non_exhaustive()
+    }
+}
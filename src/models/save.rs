@@ -0,0 +1,216 @@
+use std::{fs, path::Path};
+
+use ggez::{GameError, GameResult};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use super::{grid::GridShape, post::Effect};
+
+/// Map state written by [`save`] and read back by [`load`].
+///
+/// Holds the settings of every part of the scene, the scene itself is rebuilt from them.
+/// Sections missing from a loaded file are `None` and leave that part of the scene as it is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MapState {
+    /// Format version, bumped on incompatible changes.
+    pub version: u32,
+    pub camera: Option<CameraState>,
+    /// Colors on screen: (foreground, background), [r, g, b, a]
+    pub colors: Option<([f32; 4], [f32; 4])>,
+    pub grid: Option<GridState>,
+    pub lighting: Option<LightingState>,
+    pub cartouche: Option<CartoucheState>,
+    pub effects: Vec<EffectState>,
+}
+
+impl MapState {
+    /// Version written by this build.
+    pub const VERSION: u32 = 1;
+    const SECTIONS: [&'static str; 7] = [
+        "version",
+        "camera",
+        "colors",
+        "grid",
+        "lighting",
+        "cartouche",
+        "effects",
+    ];
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraState {
+    pub translation: [f32; 2],
+    pub zoom: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GridState {
+    pub shape: GridShape,
+    pub cell_size: f32,
+    pub opacity: f32,
+    pub visible: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LightingState {
+    pub time: f32,
+    pub speed: f32,
+    pub visible: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CartoucheState {
+    pub title: String,
+    pub dedication: String,
+    pub date: String,
+    pub visible: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EffectState {
+    pub effect: Effect,
+    pub enabled: bool,
+    pub strength: f32,
+}
+
+/// Writes `state` to `path` as pretty-printed JSON.
+pub fn save(state: &MapState, path: impl AsRef<Path>) -> GameResult {
+    let json = serde_json::to_string_pretty(state)
+        .map_err(|e| GameError::CustomError(format!("could not serialize map: {e}")))?;
+    fs::write(path, json)?;
+    Ok(())
+}
+
+/// Reads a [`MapState`] from the JSON file at `path`, along with warnings about skipped data.
+///
+/// Fails if the file is not a map or was written by a newer, incompatible version.
+/// Unknown sections, malformed sections and unknown effects are skipped with a warning
+/// so the rest of the map still loads.
+pub fn load(path: impl AsRef<Path>) -> GameResult<(MapState, Vec<String>)> {
+    let path = path.as_ref();
+    let invalid = |reason: String| GameError::CustomError(format!("{}: {reason}", path.display()));
+
+    let json = fs::read_to_string(path)?;
+    let value: Value = serde_json::from_str(&json).map_err(|e| invalid(e.to_string()))?;
+    let Value::Object(object) = value else {
+        return Err(invalid("expected a JSON object".to_string()));
+    };
+    let version = object
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| invalid("missing format version".to_string()))?;
+    if version > MapState::VERSION as u64 {
+        return Err(invalid(format!("saved by a newer version ({version})")));
+    }
+
+    let mut warnings: Vec<String> = object
+        .keys()
+        .filter(|key| !MapState::SECTIONS.contains(&key.as_str()))
+        .map(|key| format!("unknown section `{key}` skipped"))
+        .collect();
+    let effects = match object.get("effects") {
+        Some(Value::Array(effects)) => effects
+            .iter()
+            .filter_map(|effect| parse(effect, "effects", &mut warnings))
+            .collect(),
+        Some(_) => {
+            warnings.push("effects: expected a list, skipped".to_string());
+            vec![]
+        }
+        None => vec![],
+    };
+
+    let state = MapState {
+        version: version as u32,
+        camera: section(&object, "camera", &mut warnings),
+        colors: section(&object, "colors", &mut warnings),
+        grid: section(&object, "grid", &mut warnings),
+        lighting: section(&object, "lighting", &mut warnings),
+        cartouche: section(&object, "cartouche", &mut warnings),
+        effects,
+    };
+    Ok((state, warnings))
+}
+
+/// Reads the section `key` of a map file, `None` if it is missing or malformed.
+fn section<T: DeserializeOwned>(
+    object: &Map<String, Value>,
+    key: &str,
+    warnings: &mut Vec<String>,
+) -> Option<T> {
+    parse(object.get(key)?, key, warnings)
+}
+
+fn parse<T: DeserializeOwned>(value: &Value, key: &str, warnings: &mut Vec<String>) -> Option<T> {
+    match serde_json::from_value(value.clone()) {
+        Ok(parsed) => Some(parsed),
+        Err(e) => {
+            warnings.push(format!("{key}: {e}, skipped"));
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    /// Writes `json` to a file unique to the calling test and loads it.
+    fn load_json(name: &str, json: &str) -> GameResult<(MapState, Vec<String>)> {
+        let path = env::temp_dir().join(format!(
+            "cartographer-save-{name}-{}.json",
+            std::process::id()
+        ));
+        fs::write(&path, json).unwrap();
+        let loaded = load(&path);
+        fs::remove_file(&path).unwrap();
+        loaded
+    }
+
+    #[test]
+    fn unknown_section_warns() {
+        let (state, warnings) =
+            load_json("unknown-section", r#"{"version": 1, "rivers": []}"#).unwrap();
+        assert!(state.camera.is_none());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("rivers"));
+    }
+
+    #[test]
+    fn malformed_section_is_skipped() {
+        let json = r#"{
+            "version": 1,
+            "camera": {"translation": "north", "zoom": 1.0},
+            "lighting": {"time": 21.0, "speed": 0.0, "visible": true}
+        }"#;
+        let (state, warnings) = load_json("malformed-section", json).unwrap();
+        assert!(state.camera.is_none());
+        assert!(state.lighting.is_some());
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("camera"));
+    }
+
+    #[test]
+    fn newer_version_fails() {
+        let json = format!(r#"{{"version": {}}}"#, MapState::VERSION + 1);
+        assert!(load_json("newer-version", &json).is_err());
+    }
+
+    #[test]
+    fn unknown_effect_is_skipped() {
+        let json = r#"{
+            "version": 1,
+            "effects": [
+                {"effect": "Sepia", "enabled": true, "strength": 0.5},
+                {"effect": "Bloom", "enabled": true, "strength": 1.0}
+            ]
+        }"#;
+        let (state, warnings) = load_json("unknown-effect", json).unwrap();
+        assert_eq!(state.effects.len(), 1);
+        assert_eq!(state.effects[0].effect, Effect::Sepia);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("effects"));
+    }
+}
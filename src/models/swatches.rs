@@ -0,0 +1,112 @@
+use std::{fs, path::Path};
+
+use ggez::GameResult;
+
+/// A named color, [r, g, b, a]
+pub type Swatch<'a> = (&'a str, [f32; 4]);
+
+/// File formats a palette is exported to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteFormat {
+    /// GIMP palette, see [`save_gpl`].
+    Gpl,
+    /// Adobe swatch exchange, see [`save_ase`].
+    Ase,
+}
+
+impl PaletteFormat {
+    /// All formats, in the order they are exported.
+    pub const ALL: [PaletteFormat; 2] = [PaletteFormat::Gpl, PaletteFormat::Ase];
+
+    /// Returns the file extension of the format, without the dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            PaletteFormat::Gpl => "gpl",
+            PaletteFormat::Ase => "ase",
+        }
+    }
+}
+
+/// Writes `swatches` as a GIMP palette (.gpl) named `name`.
+///
+/// Alpha is dropped, GIMP palettes only hold opaque colors.
+pub fn save_gpl(name: &str, swatches: &[Swatch], path: impl AsRef<Path>) -> GameResult {
+    let mut out = format!(
+        "GIMP Palette\nName: {name}\nColumns: {}\n#\n",
+        swatches.len()
+    );
+    for (name, color) in swatches {
+        let [r, g, b] = [0, 1, 2].map(|i| (color[i].clamp(0.0, 1.0) * 255.0).round() as u8);
+        out += &format!("{r:3} {g:3} {b:3}\t{name}\n");
+    }
+    fs::write(path, out)?;
+    Ok(())
+}
+
+/// Writes `swatches` as an Adobe swatch exchange file (.ase) with global RGB colors.
+///
+/// Alpha is dropped, the format has no alpha channel.
+pub fn save_ase(swatches: &[Swatch], path: impl AsRef<Path>) -> GameResult {
+    // big endian throughout: signature, version 1.0, block count
+    let mut out: Vec<u8> = b"ASEF".to_vec();
+    out.extend(1u16.to_be_bytes());
+    out.extend(0u16.to_be_bytes());
+    out.extend((swatches.len() as u32).to_be_bytes());
+
+    for (name, color) in swatches {
+        // name as null terminated UTF-16, length counted in code units
+        let name: Vec<u16> = name.encode_utf16().chain([0]).collect();
+        let mut block = (name.len() as u16).to_be_bytes().to_vec();
+        block.extend(name.iter().flat_map(|unit| unit.to_be_bytes()));
+        block.extend(b"RGB ");
+        for channel in &color[..3] {
+            block.extend(channel.clamp(0.0, 1.0).to_be_bytes());
+        }
+        // global color
+        block.extend(0u16.to_be_bytes());
+
+        // color entry
+        out.extend(1u16.to_be_bytes());
+        out.extend((block.len() as u32).to_be_bytes());
+        out.extend(block);
+    }
+    fs::write(path, out)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn ase_layout() {
+        let path = env::temp_dir().join(format!("cartographer-ase-{}.ase", std::process::id()));
+        save_ase(&[("Ink", [1.0, 0.5, 0.0, 0.25]), ("é", [0.0; 4])], &path).unwrap();
+        let ase = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        // signature, version 1.0 and two blocks
+        assert_eq!(ase[..12], *b"ASEF\x00\x01\x00\x00\x00\x00\x00\x02");
+
+        // color entry, 2 bytes of name length, "Ink\0" in UTF-16, model, three floats, color type
+        let ink = &ase[12..];
+        let length = 2 + 4 * 2 + 4 + 3 * 4 + 2;
+        assert_eq!(ink[..6], [0, 1, 0, 0, 0, length as u8]);
+        assert_eq!(ink[6..8], [0, 4]);
+        assert_eq!(ink[8..16], [0, b'I', 0, b'n', 0, b'k', 0, 0]);
+        assert_eq!(ink[16..20], *b"RGB ");
+        assert_eq!(ink[20..24], 1.0f32.to_be_bytes());
+        assert_eq!(ink[24..28], 0.5f32.to_be_bytes());
+        assert_eq!(ink[28..32], 0.0f32.to_be_bytes());
+        assert_eq!(ink[32..34], [0, 0]);
+
+        // non-ASCII names take one code unit per character, alpha is dropped
+        let accent = &ink[6 + length..];
+        let length = 2 + 2 * 2 + 4 + 3 * 4 + 2;
+        assert_eq!(accent[..6], [0, 1, 0, 0, 0, length as u8]);
+        assert_eq!(accent[6..12], [0, 2, 0x00, 0xe9, 0, 0]);
+        assert_eq!(accent.len(), 6 + length);
+    }
+}
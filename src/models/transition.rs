@@ -0,0 +1,52 @@
+/// Smooth blend between two foreground/background color pairs.
+#[derive(Debug, Clone)]
+pub struct PaletteTransition {
+    from: ([f32; 4], [f32; 4]),
+    to: ([f32; 4], [f32; 4]),
+    elapsed: f32,
+    duration: f32,
+}
+
+impl PaletteTransition {
+    /// Creates a new [`PaletteTransition`].
+    /// Parameters:
+    /// - `from`: (foreground, background) colors at the start, [r, g, b, a]
+    /// - `to`: (foreground, background) colors at the end, [r, g, b, a]
+    /// - `duration`: length of the transition in seconds
+    pub fn new(from: ([f32; 4], [f32; 4]), to: ([f32; 4], [f32; 4]), duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            elapsed: 0.0,
+            duration,
+        }
+    }
+
+    /// Advances the transition by `dt` seconds.
+    pub fn update(&mut self, dt: f32) {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+    }
+
+    /// Returns true once the end colors are reached.
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Returns the current (foreground, background) colors.
+    pub fn colors(&self) -> ([f32; 4], [f32; 4]) {
+        let t = match self.duration > 0.0 {
+            true => self.elapsed / self.duration,
+            false => 1.0,
+        };
+        // ease in and out
+        let t = t * t * (3.0 - 2.0 * t);
+        (
+            lerp(self.from.0, self.to.0, t),
+            lerp(self.from.1, self.to.1, t),
+        )
+    }
+}
+
+fn lerp(a: [f32; 4], b: [f32; 4], t: f32) -> [f32; 4] {
+    [0, 1, 2, 3].map(|i| a[i] + (b[i] - a[i]) * t)
+}
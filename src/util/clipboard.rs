@@ -0,0 +1,48 @@
+use std::fmt;
+
+/// System clipboard, kept open for the whole session.
+///
+/// On X11 and Wayland the owning process serves copied contents on request,
+/// so without a clipboard manager they are lost once the handle is dropped.
+#[derive(Default)]
+pub struct Clipboard {
+    /// Opened on the first copy, and again after a failed attempt.
+    inner: Option<arboard::Clipboard>,
+}
+
+impl Clipboard {
+    /// Places `text` on the clipboard.
+    pub fn set_text(&mut self, text: String) -> Result<(), arboard::Error> {
+        self.open()?.set_text(text)
+    }
+
+    /// Places an RGBA8 image on the clipboard.
+    pub fn set_image(
+        &mut self,
+        width: u32,
+        height: u32,
+        pixels: Vec<u8>,
+    ) -> Result<(), arboard::Error> {
+        self.open()?.set_image(arboard::ImageData {
+            width: width as usize,
+            height: height as usize,
+            bytes: pixels.into(),
+        })
+    }
+
+    fn open(&mut self) -> Result<&mut arboard::Clipboard, arboard::Error> {
+        let clipboard = match self.inner.take() {
+            Some(clipboard) => clipboard,
+            None => arboard::Clipboard::new()?,
+        };
+        Ok(self.inner.insert(clipboard))
+    }
+}
+
+impl fmt::Debug for Clipboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Clipboard")
+            .field("open", &self.inner.is_some())
+            .finish()
+    }
+}
@@ -0,0 +1,3 @@
+pub mod clipboard;
+pub mod poisson;
+pub mod time;
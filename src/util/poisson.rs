@@ -0,0 +1,140 @@
+use std::f32::consts::TAU;
+
+use ggez::glam::Vec2;
+use rand::Rng;
+
+/// Poisson-disk sampler for placing features with a guaranteed minimum spacing.
+///
+/// Implements Bridson's algorithm over the rectangle `(0, 0)..(width, height)`.
+#[derive(Debug, Clone, Copy)]
+pub struct PoissonDisk {
+    width: f32,
+    height: f32,
+    radius: f32,
+    attempts: u32,
+}
+
+impl PoissonDisk {
+    /// Number of candidates tried around a point before it is retired.
+    pub const DEFAULT_ATTEMPTS: u32 = 30;
+
+    /// Creates a new [`PoissonDisk`] sampler.
+    /// Parameters:
+    /// - `width`, `height`: size of the sampled area
+    /// - `radius`: minimum distance between any two points
+    /// - `attempts`: candidates tried per active point (see [`Self::DEFAULT_ATTEMPTS`])
+    pub fn new(width: f32, height: f32, radius: f32, attempts: u32) -> PoissonDisk {
+        Self {
+            width,
+            height,
+            radius,
+            attempts,
+        }
+    }
+
+    /// Generates points using the given random number generator.
+    ///
+    /// Returns an empty vector if the area or radius is not positive.
+    pub fn sample(&self, rng: &mut impl Rng) -> Vec<Vec2> {
+        if self.width <= 0.0 || self.height <= 0.0 || self.radius <= 0.0 {
+            return vec![];
+        }
+
+        let cell = self.radius / std::f32::consts::SQRT_2;
+        let cols = (self.width / cell).ceil() as usize;
+        let rows = (self.height / cell).ceil() as usize;
+        let mut grid: Vec<Option<usize>> = vec![None; cols * rows];
+        let cell_of = |p: Vec2| {
+            let x = ((p.x / cell) as usize).min(cols - 1);
+            let y = ((p.y / cell) as usize).min(rows - 1);
+            (x, y)
+        };
+
+        let mut points = vec![];
+        let mut active = vec![];
+
+        let first = Vec2::new(
+            rng.gen_range(0.0..self.width),
+            rng.gen_range(0.0..self.height),
+        );
+        let (x, y) = cell_of(first);
+        grid[y * cols + x] = Some(0);
+        points.push(first);
+        active.push(0);
+
+        while !active.is_empty() {
+            let idx = rng.gen_range(0..active.len());
+            let origin = points[active[idx]];
+            let mut found = false;
+
+            for _ in 0..self.attempts {
+                let angle = rng.gen_range(0.0..TAU);
+                let dist = rng.gen_range(self.radius..2.0 * self.radius);
+                let candidate = origin + Vec2::new(angle.cos(), angle.sin()) * dist;
+                if candidate.x < 0.0
+                    || candidate.y < 0.0
+                    || candidate.x >= self.width
+                    || candidate.y >= self.height
+                {
+                    continue;
+                }
+
+                let (cx, cy) = cell_of(candidate);
+                let near = (cy.saturating_sub(2)..(cy + 3).min(rows))
+                    .flat_map(|y| (cx.saturating_sub(2)..(cx + 3).min(cols)).map(move |x| (x, y)))
+                    .filter_map(|(x, y)| grid[y * cols + x])
+                    .any(|i| points[i].distance_squared(candidate) < self.radius * self.radius);
+                if near {
+                    continue;
+                }
+
+                grid[cy * cols + cx] = Some(points.len());
+                active.push(points.len());
+                points.push(candidate);
+                found = true;
+                break;
+            }
+
+            // no room left around this point
+            if !found {
+                active.swap_remove(idx);
+            }
+        }
+
+        points
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    #[test]
+    fn points_are_spaced_and_in_bounds() {
+        let sampler = PoissonDisk::new(200.0, 120.0, 10.0, PoissonDisk::DEFAULT_ATTEMPTS);
+        let points = sampler.sample(&mut StdRng::seed_from_u64(7));
+        assert!(points.len() > 1);
+        for (i, a) in points.iter().enumerate() {
+            assert!((0.0..200.0).contains(&a.x) && (0.0..120.0).contains(&a.y));
+            for b in &points[i + 1..] {
+                assert!(a.distance(*b) >= sampler.radius);
+            }
+        }
+    }
+
+    #[test]
+    fn empty_without_area_or_radius() {
+        let mut rng = StdRng::seed_from_u64(7);
+        for (width, height, radius) in [
+            (0.0, 100.0, 10.0),
+            (100.0, -1.0, 10.0),
+            (100.0, 100.0, 0.0),
+            (100.0, 100.0, -5.0),
+        ] {
+            let sampler = PoissonDisk::new(width, height, radius, PoissonDisk::DEFAULT_ATTEMPTS);
+            assert!(sampler.sample(&mut rng).is_empty());
+        }
+    }
+}
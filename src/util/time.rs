@@ -0,0 +1,118 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Calendar date and time of day in UTC.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: u32,
+}
+
+impl DateTime {
+    /// Returns the current UTC date and time.
+    pub fn now() -> DateTime {
+        Self::from(SystemTime::now())
+    }
+
+    /// Formats as `YYYYMMDD-HHMMSS`, suitable for file names.
+    pub fn compact(&self) -> String {
+        format!(
+            "{:04}{:02}{:02}-{:02}{:02}{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+
+    /// Formats as `YYYY-MM-DD HH:MM:SS`.
+    pub fn iso(&self) -> String {
+        format!(
+            "{:04}-{:02}-{:02} {}",
+            self.year,
+            self.month,
+            self.day,
+            self.clock()
+        )
+    }
+
+    /// Formats the time of day as `HH:MM:SS`.
+    pub fn clock(&self) -> String {
+        format!("{:02}:{:02}:{:02}", self.hour, self.minute, self.second)
+    }
+}
+
+impl From<SystemTime> for DateTime {
+    fn from(time: SystemTime) -> Self {
+        let secs = match time.duration_since(UNIX_EPOCH) {
+            Ok(elapsed) => elapsed.as_secs() as i64,
+            Err(e) => -(e.duration().as_secs() as i64),
+        };
+        let (days, rem) = (secs.div_euclid(86_400), secs.rem_euclid(86_400));
+
+        // days since the epoch to a civil date, see
+        // http://howardhinnant.github.io/date_algorithms.html#civil_from_days
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+
+        Self {
+            year,
+            month,
+            day,
+            hour: (rem / 3600) as u32,
+            minute: (rem % 3600 / 60) as u32,
+            second: (rem % 60) as u32,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    /// Returns the date `secs` seconds from the epoch, negative for earlier instants.
+    fn at(secs: i64) -> DateTime {
+        let offset = Duration::from_secs(secs.unsigned_abs());
+        match secs < 0 {
+            true => DateTime::from(UNIX_EPOCH - offset),
+            false => DateTime::from(UNIX_EPOCH + offset),
+        }
+    }
+
+    #[test]
+    fn epoch() {
+        assert_eq!(at(0).iso(), "1970-01-01 00:00:00");
+    }
+
+    #[test]
+    fn leap_day() {
+        assert_eq!(at(951_825_600).iso(), "2000-02-29 12:00:00");
+        assert_eq!(at(951_868_800).iso(), "2000-03-01 00:00:00");
+    }
+
+    #[test]
+    fn century_without_leap_day() {
+        assert_eq!(at(4_107_542_399).iso(), "2100-02-28 23:59:59");
+        assert_eq!(at(4_107_542_400).iso(), "2100-03-01 00:00:00");
+    }
+
+    #[test]
+    fn before_epoch() {
+        assert_eq!(at(-1).iso(), "1969-12-31 23:59:59");
+        assert_eq!(at(-2_208_988_800).iso(), "1900-01-01 00:00:00");
+    }
+
+    #[test]
+    fn compact_format() {
+        assert_eq!(at(951_825_600).compact(), "20000229-120000");
+    }
+}